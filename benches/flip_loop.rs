@@ -0,0 +1,171 @@
+use avalanche_effect::app::{
+    ColoredText, algorithms, automatic, bias, manipulations, random, worker::Progress,
+};
+use criterion::{BenchmarkId, Criterion, Throughput, criterion_group, criterion_main};
+use ratatui::style::Color;
+use rayon::prelude::*;
+use sha2::{Digest, Sha256};
+use std::hint::black_box;
+use std::sync::{Arc, Mutex};
+
+const SAMPLED: usize = 64;
+
+// The loop this crate started with, kept as the baseline: the message is
+// expanded to one byte per bit, copied for every flip, packed back, and the
+// digests are compared as binary strings, with results pushed through a mutex.
+fn baseline(text: &str, positions: &[usize]) -> Vec<f64> {
+    let original_bits = manipulations::str_to_bits(text);
+
+    let mut hasher = Sha256::new();
+    hasher.update(text.as_bytes());
+    let result = hasher.finalize();
+    let initial_hash: String = result.iter().map(|b| format!("{:08b}", b)).collect();
+
+    let statistics = Arc::new(Mutex::new(Vec::new()));
+    positions.par_iter().for_each(|&idx| {
+        let changed_bits = manipulations::reverse_bit(&original_bits, idx);
+
+        let changed_bytes = manipulations::bits_to_bytes(&changed_bits);
+        let mut hasher = Sha256::new();
+        hasher.update(&changed_bytes);
+        let result = hasher.finalize();
+        let changed_hash: String = result.iter().map(|b| format!("{:08b}", b)).collect();
+
+        let (percent, ..) = percent_difference(&initial_hash, &changed_hash);
+
+        let mut stats_lock = statistics.lock().unwrap();
+        stats_lock.push(percent);
+    });
+    Arc::try_unwrap(statistics).unwrap().into_inner().unwrap()
+}
+
+// The string comparison the baseline loop called for every flip.
+fn percent_difference(s1: &str, s2: &str) -> (f64, Vec<ColoredText>, Vec<ColoredText>) {
+    let chars1: Vec<char> = s1.chars().collect();
+    let chars2: Vec<char> = s2.chars().collect();
+
+    if chars1.len() != chars2.len() {
+        return (0.0, Vec::new(), Vec::new());
+    }
+
+    let mut older = Vec::new();
+    let mut newer = Vec::new();
+    let mut diff_count = 0;
+
+    for i in 0..chars1.len() {
+        if chars1[i] != chars2[i] {
+            diff_count += 1;
+            older.push(ColoredText {
+                text: chars1[i].to_string(),
+                color: Color::Red,
+            });
+            newer.push(ColoredText {
+                text: chars2[i].to_string(),
+                color: Color::Yellow,
+            });
+        } else {
+            older.push(ColoredText {
+                text: chars1[i].to_string(),
+                color: Color::Green,
+            });
+            newer.push(ColoredText {
+                text: chars2[i].to_string(),
+                color: Color::Green,
+            });
+        }
+    }
+
+    let percent = if chars1.is_empty() {
+        0.0
+    } else {
+        (diff_count as f64 / chars1.len() as f64) * 100.0
+    };
+
+    (percent, older, newer)
+}
+
+fn flip_positions(data: &[u8], positions: &[usize]) -> Vec<automatic::FlipOutcome> {
+    let algorithm = algorithms::find("SHA-256").unwrap();
+    let original_digest = algorithm.hash(data);
+    automatic::flip_positions(
+        algorithm,
+        data,
+        &original_digest,
+        positions,
+        &Progress::none(),
+    )
+}
+
+fn check_same_results(text: &str, positions: &[usize]) {
+    let mut expected = baseline(text, positions);
+    let mut actual: Vec<f64> = flip_positions(text.as_bytes(), positions)
+        .iter()
+        .map(|flip| flip.percent)
+        .collect();
+    expected.sort_by(f64::total_cmp);
+    actual.sort_by(f64::total_cmp);
+    assert_eq!(actual, expected);
+}
+
+// Every bit of a short message, as the automatic mode does.
+fn exhaustive(c: &mut Criterion) {
+    let mut group = c.benchmark_group("exhaustive");
+    for len in [64, 1024] {
+        let text = "a".repeat(len);
+        let positions: Vec<usize> = (0..len * 8).collect();
+        check_same_results(&text, &positions);
+        group.throughput(Throughput::Elements(len as u64 * 8));
+        group.bench_with_input(BenchmarkId::new("flip_positions", len), &text, |b, text| {
+            b.iter(|| flip_positions(black_box(text.as_bytes()), &positions))
+        });
+        group.bench_with_input(BenchmarkId::new("baseline", len), &text, |b, text| {
+            b.iter(|| baseline(black_box(text), &positions))
+        });
+    }
+    group.finish();
+}
+
+// Throughput in message bytes hashed, so the multi-megabyte cases compare
+// the cost of preparing each flipped message against the cost of hashing it.
+fn sampled_large(c: &mut Criterion) {
+    let mut group = c.benchmark_group("sampled");
+    group.sample_size(10);
+    for mib in [1, 4] {
+        let text = "Z".repeat(mib << 20);
+        let positions = random::sample_indices(text.len() * 8, SAMPLED, &mut random::Rng::new(1));
+        group.throughput(Throughput::Bytes((SAMPLED * text.len()) as u64));
+        group.bench_with_input(
+            BenchmarkId::new("flip_positions", format!("{mib}MiB")),
+            &text,
+            |b, text| b.iter(|| flip_positions(black_box(text.as_bytes()), &positions)),
+        );
+        group.bench_with_input(
+            BenchmarkId::new("baseline", format!("{mib}MiB")),
+            &text,
+            |b, text| b.iter(|| baseline(black_box(text), &positions)),
+        );
+    }
+    group.finish();
+}
+
+fn output_bit_counts(c: &mut Criterion) {
+    let data = vec![0x3cu8; 64];
+    let mut group = c.benchmark_group("bias");
+    group.throughput(Throughput::Elements(
+        (data.len() * 8 * (bias::BIAS_RANDOM_MESSAGES + 1)) as u64,
+    ));
+    group.bench_function("SHA-256/64", |b| {
+        b.iter(|| {
+            bias::output_bit_counts(
+                algorithms::ALGORITHMS[0],
+                black_box(&data),
+                bias::BIAS_RANDOM_MESSAGES,
+                random::DEFAULT_SEED,
+            )
+        })
+    });
+    group.finish();
+}
+
+criterion_group!(benches, exhaustive, sampled_large, output_bit_counts);
+criterion_main!(benches);
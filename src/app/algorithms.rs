@@ -0,0 +1,234 @@
+use crate::app::checksums;
+use crate::app::ciphers::{Cipher, CipherTarget, FlippedInput};
+use crate::app::noncrypto::{self, IntegerHash};
+use sha2::digest::Digest;
+use std::marker::PhantomData;
+
+pub trait HashAlgorithm: Sync {
+    fn name(&self) -> &'static str;
+    fn output_bits(&self) -> usize;
+    fn hash(&self, data: &[u8]) -> Vec<u8>;
+
+    // Largest input in bytes, for targets such as ciphers that take a fixed-size input.
+    fn input_len(&self) -> Option<usize> {
+        None
+    }
+}
+
+pub struct DigestAlgorithm<D> {
+    name: &'static str,
+    _digest: PhantomData<fn() -> D>,
+}
+
+impl<D> DigestAlgorithm<D> {
+    pub const fn new(name: &'static str) -> Self {
+        DigestAlgorithm {
+            name,
+            _digest: PhantomData,
+        }
+    }
+}
+
+impl<D: Digest> HashAlgorithm for DigestAlgorithm<D> {
+    fn name(&self) -> &'static str {
+        self.name
+    }
+
+    fn output_bits(&self) -> usize {
+        <D as Digest>::output_size() * 8
+    }
+
+    fn hash(&self, data: &[u8]) -> Vec<u8> {
+        D::digest(data).to_vec()
+    }
+}
+
+pub struct Blake3;
+
+impl HashAlgorithm for Blake3 {
+    fn name(&self) -> &'static str {
+        "BLAKE3"
+    }
+
+    fn output_bits(&self) -> usize {
+        blake3::OUT_LEN * 8
+    }
+
+    fn hash(&self, data: &[u8]) -> Vec<u8> {
+        blake3::hash(data).as_bytes().to_vec()
+    }
+}
+
+pub static ALGORITHMS: &[&dyn HashAlgorithm] = &[
+    &DigestAlgorithm::<sha2::Sha256>::new("SHA-256"),
+    &DigestAlgorithm::<sha2::Sha224>::new("SHA-224"),
+    &DigestAlgorithm::<sha2::Sha384>::new("SHA-384"),
+    &DigestAlgorithm::<sha2::Sha512>::new("SHA-512"),
+    &DigestAlgorithm::<sha2::Sha512_256>::new("SHA-512/256"),
+    &DigestAlgorithm::<sha1::Sha1>::new("SHA-1"),
+    &DigestAlgorithm::<md5::Md5>::new("MD5"),
+    &DigestAlgorithm::<sha3::Sha3_256>::new("SHA3-256"),
+    &DigestAlgorithm::<sha3::Keccak256>::new("Keccak-256"),
+    &DigestAlgorithm::<blake2::Blake2b512>::new("BLAKE2b"),
+    &DigestAlgorithm::<blake2::Blake2s256>::new("BLAKE2s"),
+    &Blake3,
+    &IntegerHash::new("FNV-1a-32", 32, noncrypto::fnv1a_32),
+    &IntegerHash::new("FNV-1a-64", 64, noncrypto::fnv1a_64),
+    &IntegerHash::new("Murmur3-32", 32, noncrypto::murmur3_32),
+    &IntegerHash::new("Murmur3-128", 128, noncrypto::murmur3_128),
+    &IntegerHash::new("xxHash32", 32, noncrypto::xxhash32),
+    &IntegerHash::new("xxHash64", 64, noncrypto::xxhash64),
+    &IntegerHash::new("XXH3-64", 64, noncrypto::xxh3_64),
+    &IntegerHash::new("XXH3-128", 128, noncrypto::xxh3_128),
+    &IntegerHash::new("CityHash128", 128, noncrypto::cityhash_128),
+    &IntegerHash::new("SipHash-1-3", 64, noncrypto::siphash_1_3),
+    &IntegerHash::new("SipHash-2-4", 64, noncrypto::siphash_2_4),
+    &IntegerHash::new("DefaultHasher", 64, noncrypto::default_hasher),
+    &IntegerHash::new("CRC-8", 8, checksums::crc_8),
+    &IntegerHash::new("CRC-16/ARC", 16, checksums::crc_16_arc),
+    &IntegerHash::new("CRC-16/CCITT", 16, checksums::crc_16_ccitt),
+    &IntegerHash::new("CRC-32", 32, checksums::crc_32),
+    &IntegerHash::new("CRC-32C", 32, checksums::crc_32c),
+    &IntegerHash::new("CRC-64/ECMA", 64, checksums::crc_64_ecma),
+    &IntegerHash::new("CRC-64/XZ", 64, checksums::crc_64_xz),
+    &IntegerHash::new("Adler-32", 32, checksums::adler_32),
+    &IntegerHash::new("Fletcher-16", 16, checksums::fletcher_16),
+    &IntegerHash::new("Fletcher-32", 32, checksums::fletcher_32),
+    &CipherTarget::new(Cipher::Aes128, FlippedInput::Plaintext, "AES-128"),
+    &CipherTarget::new(Cipher::Aes128, FlippedInput::Key, "AES-128-key"),
+    &CipherTarget::new(Cipher::Aes256, FlippedInput::Plaintext, "AES-256"),
+    &CipherTarget::new(Cipher::Aes256, FlippedInput::Key, "AES-256-key"),
+    &CipherTarget::new(Cipher::ChaCha20, FlippedInput::Plaintext, "ChaCha20"),
+    &CipherTarget::new(Cipher::ChaCha20, FlippedInput::Key, "ChaCha20-key"),
+];
+
+pub fn check_input(algorithm: &dyn HashAlgorithm, data: &[u8]) -> Result<(), String> {
+    match algorithm.input_len() {
+        Some(len) if data.len() > len => Err(format!(
+            "{} takes at most {} bytes of input, got {}",
+            algorithm.name(),
+            len,
+            data.len()
+        )),
+        _ => Ok(()),
+    }
+}
+
+// Also resolves the reduced-round variants, e.g. "SHA-256-R8".
+pub fn find(name: &str) -> Option<&'static dyn HashAlgorithm> {
+    ALGORITHMS
+        .iter()
+        .copied()
+        .find(|algorithm| algorithm.name().eq_ignore_ascii_case(name))
+        .or_else(|| {
+            crate::app::sha256::ROUND_VARIANTS
+                .iter()
+                .find(|variant| variant.name().eq_ignore_ascii_case(name))
+                .map(|variant| variant as &dyn HashAlgorithm)
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn hex(bytes: &[u8]) -> String {
+        bytes.iter().map(|b| format!("{:02x}", b)).collect()
+    }
+
+    #[test]
+    fn test_registry_names_are_unique() {
+        for (i, a) in ALGORITHMS.iter().enumerate() {
+            for b in ALGORITHMS.iter().skip(i + 1) {
+                assert_ne!(a.name(), b.name());
+            }
+        }
+    }
+
+    #[test]
+    fn test_output_bits_match_digest_length() {
+        for algorithm in ALGORITHMS {
+            assert_eq!(algorithm.hash(b"abc").len() * 8, algorithm.output_bits());
+        }
+    }
+
+    #[test]
+    fn test_known_output_sizes() {
+        let expected = [
+            ("SHA-224", 224),
+            ("SHA-256", 256),
+            ("SHA-384", 384),
+            ("SHA-512", 512),
+            ("SHA-512/256", 256),
+            ("SHA-1", 160),
+            ("MD5", 128),
+            ("SHA3-256", 256),
+            ("Keccak-256", 256),
+            ("BLAKE2b", 512),
+            ("BLAKE2s", 256),
+            ("BLAKE3", 256),
+            ("FNV-1a-32", 32),
+            ("xxHash64", 64),
+            ("Murmur3-128", 128),
+            ("CRC-8", 8),
+            ("CRC-64/XZ", 64),
+            ("Fletcher-16", 16),
+            ("AES-128", 128),
+            ("AES-256-key", 128),
+            ("ChaCha20", 512),
+        ];
+        for (name, bits) in expected {
+            assert_eq!(find(name).unwrap().output_bits(), bits);
+        }
+    }
+
+    #[test]
+    fn test_sha256_test_vector() {
+        let digest = find("SHA-256").unwrap().hash(b"abc");
+        assert_eq!(
+            hex(&digest),
+            "ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad"
+        );
+    }
+
+    #[test]
+    fn test_md5_test_vector() {
+        let digest = find("MD5").unwrap().hash(b"abc");
+        assert_eq!(hex(&digest), "900150983cd24fb0d6963f7d28e17f72");
+    }
+
+    #[test]
+    fn test_keccak_differs_from_sha3() {
+        let keccak = find("Keccak-256").unwrap().hash(b"");
+        let sha3 = find("SHA3-256").unwrap().hash(b"");
+        assert_ne!(keccak, sha3);
+        assert_eq!(
+            hex(&keccak),
+            "c5d2460186f7233c927e7db2dcc703c0e500b653ca82273b7bfad8045d85a470"
+        );
+    }
+
+    #[test]
+    fn test_find_is_case_insensitive() {
+        assert_eq!(find("sha-256").unwrap().name(), "SHA-256");
+        assert_eq!(find("blake3").unwrap().name(), "BLAKE3");
+        assert!(find("unknown").is_none());
+    }
+
+    #[test]
+    fn test_find_reduced_round_variant() {
+        let variant = find("sha-256-r8").unwrap();
+        assert_eq!(variant.name(), "SHA-256-R8");
+        assert!(find("SHA-256-R65").is_none());
+    }
+
+    #[test]
+    fn test_check_input_limits_cipher_targets() {
+        assert!(check_input(find("SHA-256").unwrap(), &[0; 100]).is_ok());
+        assert!(check_input(find("AES-128").unwrap(), &[0; 16]).is_ok());
+        assert_eq!(
+            check_input(find("AES-128-key").unwrap(), &[0; 17]).unwrap_err(),
+            "AES-128-key takes at most 16 bytes of input, got 17"
+        );
+    }
+}
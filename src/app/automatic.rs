@@ -1,7 +1,10 @@
 use crate::app::*;
+use export::RunExport;
 use rayon::prelude::*;
-use sha2::{Digest, Sha256};
-use std::sync::{Arc, Mutex};
+use sampling::{Sampler, SamplingPlan, Strata, Target};
+use statistics::{Binomial, GoodnessOfFit, MeanInterval, Summary};
+use std::time::Duration;
+use worker::Progress;
 
 impl App {
     pub fn switch_to_automatic(&mut self) {
@@ -11,47 +14,479 @@ impl App {
         self.messages.clear();
         self.colored_messages.clear();
         self.messages.push("Enter string to hash:".to_string());
-        self.process_automatic();
     }
+
+    // Returns after any stage that comes back cancelled; the worker drops
+    // whatever was pushed so far.
     pub fn process_automatic(&mut self) {
-        let original_bits = manipulations::str_to_bits(&self.original_text);
-
-        let mut hasher = Sha256::new();
-        hasher.update(self.original_text.as_bytes());
-        let result = hasher.finalize();
-        let initial_hash: String = result.iter().map(|b| format!("{:08b}", b)).collect();
-
-        let statistics = Arc::new(Mutex::new(Vec::new()));
-
-        let time = std::time::Instant::now();
-        (0..original_bits.len()).into_par_iter().for_each(|idx| {
-            let changed_bits = manipulations::reverse_bit(&original_bits, idx);
-
-            let changed_bytes = manipulations::bits_to_bytes(&changed_bits);
-            let mut hasher = Sha256::new();
-            hasher.update(&changed_bytes);
-            let result = hasher.finalize();
-            let changed_hash: String = result.iter().map(|b| format!("{:08b}", b)).collect();
-
-            let (percent, ..) = statistics::percent_difference(&initial_hash, &changed_hash);
-
-            let mut stats_lock = statistics.lock().unwrap();
-            stats_lock.push(percent);
-        });
-        let elapsed = time.elapsed();
-
-        let stats = statistics.lock().unwrap();
-        let max_val = stats.iter().cloned().fold(f64::NEG_INFINITY, f64::max);
-        let min_val = stats.iter().cloned().fold(f64::INFINITY, f64::min);
-        let avg = stats.iter().cloned().sum::<f64>() / stats.len() as f64;
-
-        self.messages.push("=== Summary ===".to_string());
-        self.messages.push(format!("Minimum: {:.2}%", min_val));
-        self.messages.push(format!("Maximum: {:.2}%", max_val));
-        self.messages.push(format!("Average: {:.2}%", avg));
-        self.messages
-            .push(format!("Computation time: {:?}", elapsed));
+        let report = analyze_auto_with_progress(
+            self.algorithm(),
+            self.message(),
+            &SamplingPlan {
+                seed: self.seed,
+                ..self.sampling
+            },
+            &self.progress,
+        );
+        if self.progress.is_cancelled() {
+            return;
+        }
+        self.messages.extend(report.lines(self.significance_level));
+        self.push_sensitivity(&report);
+        self.push_bias(&bias::from_report(&report), 1);
+        let sweep = weight_sweep_with_progress(
+            self.algorithm(),
+            self.message(),
+            SWEEP_MAX_WEIGHT,
+            SWEEP_TRIALS,
+            self.seed,
+            &self.progress,
+        );
+        if self.progress.is_cancelled() {
+            return;
+        }
+        self.messages.extend(sweep_lines(&sweep, self.seed));
+        let linearity = linearity::detect_with_progress(
+            self.algorithm(),
+            self.message(),
+            linearity::LINEARITY_MESSAGES,
+            self.seed,
+            &self.progress,
+        );
+        if self.progress.is_cancelled() {
+            return;
+        }
+        self.messages.extend(linearity::linearity_lines(&linearity));
         self.messages.push("Press Enter to continue...".to_string());
+        self.last_export = Some(RunExport::from_automatic(&report));
+        self.histogram = DistanceHistogram::from_report(&report);
+    }
+}
+
+// Inputs above this many bits are sampled instead of flipping every bit.
+pub const MAX_EXHAUSTIVE_BITS: usize = 1 << 16;
+pub const SAMPLED_FLIPS: usize = 4096;
+pub const SWEEP_MAX_WEIGHT: usize = 16;
+pub const SWEEP_TRIALS: usize = 64;
+
+pub struct FlipOutcome {
+    pub bit_index: usize,
+    pub hamming_distance: usize,
+    pub percent: f64,
+    pub flipped_digest: Vec<u8>,
+}
+
+pub struct AutomaticReport {
+    pub algorithm: &'static dyn HashAlgorithm,
+    pub input_len: usize,
+    pub original_digest: Vec<u8>,
+    // Sorted by bit index.
+    pub flips: Vec<FlipOutcome>,
+    // Seed of the random bit positions when not every bit was flipped.
+    pub sample_seed: Option<u64>,
+    pub strata: Strata,
+    pub elapsed: Duration,
+}
+
+pub fn analyze(algorithm: &'static dyn HashAlgorithm, data: &[u8]) -> AutomaticReport {
+    let positions: Vec<usize> = (0..data.len() * 8).collect();
+    analyze_positions(algorithm, data, &positions, &Progress::none())
+}
+
+pub fn analyze_sampled(
+    algorithm: &'static dyn HashAlgorithm,
+    data: &[u8],
+    samples: usize,
+    seed: u64,
+) -> AutomaticReport {
+    let plan = SamplingPlan {
+        seed,
+        ..SamplingPlan::new(Strata::Uniform, Target::Count(samples))
+    };
+    analyze_monte_carlo(algorithm, data, &plan)
+}
+
+pub fn analyze_auto(
+    algorithm: &'static dyn HashAlgorithm,
+    data: &[u8],
+    plan: &SamplingPlan,
+) -> AutomaticReport {
+    analyze_auto_with_progress(algorithm, data, plan, &Progress::none())
+}
+
+// Large inputs are sampled according to `plan`. The report only holds the
+// flips done so far when the job was cancelled.
+pub fn analyze_auto_with_progress(
+    algorithm: &'static dyn HashAlgorithm,
+    data: &[u8],
+    plan: &SamplingPlan,
+    progress: &Progress,
+) -> AutomaticReport {
+    if data.len() * 8 > MAX_EXHAUSTIVE_BITS {
+        analyze_monte_carlo_with_progress(algorithm, data, plan, progress)
+    } else {
+        let positions: Vec<usize> = (0..data.len() * 8).collect();
+        analyze_positions(algorithm, data, &positions, progress)
+    }
+}
+
+fn analyze_positions(
+    algorithm: &'static dyn HashAlgorithm,
+    data: &[u8],
+    positions: &[usize],
+    progress: &Progress,
+) -> AutomaticReport {
+    progress.stage("Flipping bits", positions.len());
+    let original_digest = algorithm.hash(data);
+    let time = std::time::Instant::now();
+    let mut flips = flip_positions(algorithm, data, &original_digest, positions, progress);
+    let elapsed = time.elapsed();
+
+    flips.sort_by_key(|flip| flip.bit_index);
+    AutomaticReport {
+        algorithm,
+        input_len: data.len(),
+        original_digest,
+        flips,
+        sample_seed: None,
+        strata: Strata::Uniform,
+        elapsed,
+    }
+}
+
+// Flips the bits `reference` flipped, so the flips of two algorithms pair up.
+pub fn analyze_same_flips(
+    algorithm: &'static dyn HashAlgorithm,
+    data: &[u8],
+    reference: &AutomaticReport,
+    progress: &Progress,
+) -> AutomaticReport {
+    let positions: Vec<usize> = reference.flips.iter().map(|flip| flip.bit_index).collect();
+    AutomaticReport {
+        sample_seed: reference.sample_seed,
+        strata: reference.strata,
+        ..analyze_positions(algorithm, data, &positions, progress)
+    }
+}
+
+pub fn analyze_monte_carlo(
+    algorithm: &'static dyn HashAlgorithm,
+    data: &[u8],
+    plan: &SamplingPlan,
+) -> AutomaticReport {
+    analyze_monte_carlo_with_progress(algorithm, data, plan, &Progress::none())
+}
+
+// Draws positions in batches until the target count is reached or, for a
+// width target, the confidence interval of the mean is narrow enough.
+pub fn analyze_monte_carlo_with_progress(
+    algorithm: &'static dyn HashAlgorithm,
+    data: &[u8],
+    plan: &SamplingPlan,
+    progress: &Progress,
+) -> AutomaticReport {
+    let total_bits = data.len() * 8;
+    let (goal, width) = match plan.target {
+        Target::Count(count) => (count.min(total_bits), None),
+        Target::Width(width) => (sampling::MAX_ADAPTIVE_FLIPS.min(total_bits), Some(width)),
+    };
+    progress.stage("Sampling bits", goal);
+    let original_digest = algorithm.hash(data);
+    let mut sampler = Sampler::new(total_bits, plan);
+
+    let time = std::time::Instant::now();
+    let mut flips = Vec::new();
+    while flips.len() < goal && !progress.is_cancelled() {
+        let batch = match width {
+            Some(_) => sampling::BATCH_FLIPS.min(goal - flips.len()),
+            None => goal - flips.len(),
+        };
+        let positions = sampler.next_batch(batch);
+        flips.extend(flip_positions(
+            algorithm,
+            data,
+            &original_digest,
+            &positions,
+            progress,
+        ));
+        if let Some(width) = width {
+            let percents: Vec<f64> = flips.iter().map(|flip| flip.percent).collect();
+            if statistics::mean_interval(&percents, total_bits, sampling::CONFIDENCE)
+                .is_some_and(|interval| interval.width() <= width)
+            {
+                break;
+            }
+        }
+    }
+    let elapsed = time.elapsed();
+
+    flips.sort_by_key(|flip| flip.bit_index);
+    AutomaticReport {
+        algorithm,
+        input_len: data.len(),
+        original_digest,
+        flips,
+        sample_seed: Some(plan.seed),
+        strata: plan.strata,
+        elapsed,
+    }
+}
+
+// The per-flip loop shared by every strategy. Each rayon job flips bits in
+// its own copy of the message and flips them back, so the message is never
+// cloned per flip. Unsorted; stops early when the job is cancelled.
+pub fn flip_positions(
+    algorithm: &'static dyn HashAlgorithm,
+    data: &[u8],
+    original_digest: &[u8],
+    positions: &[usize],
+    progress: &Progress,
+) -> Vec<FlipOutcome> {
+    let output_bits = original_digest.len() * 8;
+    positions
+        .par_iter()
+        .map_init(
+            || data.to_vec(),
+            |buffer, &idx| {
+                if !progress.step() {
+                    return None;
+                }
+                manipulations::flip_bit_in_bytes(buffer, idx);
+                let flipped_digest = algorithm.hash(buffer);
+                manipulations::flip_bit_in_bytes(buffer, idx);
+
+                let hamming_distance = diff::hamming_distance(original_digest, &flipped_digest);
+                Some(FlipOutcome {
+                    bit_index: idx,
+                    hamming_distance,
+                    percent: diff::percent(hamming_distance, output_bits),
+                    flipped_digest,
+                })
+            },
+        )
+        .flatten()
+        .collect()
+}
+
+pub struct WeightPoint {
+    // Hamming weight of the input difference.
+    pub weight: usize,
+    pub summary: Summary,
+}
+
+// Average avalanche for random k-bit input differences, k = 1..=max_weight.
+pub fn weight_sweep(
+    algorithm: &'static dyn HashAlgorithm,
+    data: &[u8],
+    max_weight: usize,
+    trials: usize,
+    seed: u64,
+) -> Vec<WeightPoint> {
+    weight_sweep_with_progress(algorithm, data, max_weight, trials, seed, &Progress::none())
+}
+
+pub fn weight_sweep_with_progress(
+    algorithm: &'static dyn HashAlgorithm,
+    data: &[u8],
+    max_weight: usize,
+    trials: usize,
+    seed: u64,
+    progress: &Progress,
+) -> Vec<WeightPoint> {
+    let total_bits = data.len() * 8;
+    let original_digest = algorithm.hash(data);
+    let max_weight = max_weight.min(total_bits);
+    progress.stage("Weight sweep", max_weight * trials);
+
+    (1..=max_weight)
+        .filter_map(|weight| {
+            let percents: Vec<f64> = (0..trials)
+                .into_par_iter()
+                .map_init(
+                    || data.to_vec(),
+                    |buffer, trial| {
+                        if !progress.step() {
+                            return None;
+                        }
+                        let mut rng =
+                            random::Rng::for_stream(seed, (weight * trials + trial) as u64);
+                        let bits = random::sample_indices(total_bits, weight, &mut rng);
+                        flips::apply_flips(buffer, &bits);
+                        let flipped_digest = algorithm.hash(buffer);
+                        flips::apply_flips(buffer, &bits);
+
+                        let distance = diff::hamming_distance(&original_digest, &flipped_digest);
+                        Some(diff::percent(distance, original_digest.len() * 8))
+                    },
+                )
+                .flatten()
+                .collect();
+            Summary::from_values(&percents).map(|summary| WeightPoint { weight, summary })
+        })
+        .collect()
+}
+
+pub fn sweep_lines(points: &[WeightPoint], seed: u64) -> Vec<String> {
+    if points.is_empty() {
+        return Vec::new();
+    }
+    let mut lines = vec![
+        "=== Avalanche by input difference weight ===".to_string(),
+        format!("Random differences (seed {:#x})", seed),
+    ];
+    for point in points {
+        lines.push(format!(
+            "k = {:>2}: mean {:.2}%, std {:.2}% ({} trials)",
+            point.weight, point.summary.mean, point.summary.std_dev, point.summary.count
+        ));
+    }
+    lines
+}
+
+impl AutomaticReport {
+    pub fn percentages(&self) -> Vec<f64> {
+        self.flips.iter().map(|flip| flip.percent).collect()
+    }
+
+    pub fn summary(&self) -> Option<Summary> {
+        Summary::from_values(&self.percentages())
+    }
+
+    pub fn ideal(&self) -> Binomial {
+        Binomial::ideal(self.algorithm.output_bits())
+    }
+
+    // Only meaningful for sampled runs; an exhaustive run has no error.
+    pub fn mean_interval(&self) -> Option<MeanInterval> {
+        statistics::mean_interval(
+            &self.percentages(),
+            self.input_len * 8,
+            sampling::CONFIDENCE,
+        )
+    }
+
+    fn to_bits(&self, percent: f64) -> f64 {
+        percent * self.algorithm.output_bits() as f64 / 100.0
+    }
+
+    pub fn distances(&self) -> Vec<usize> {
+        self.flips
+            .iter()
+            .map(|flip| flip.hamming_distance)
+            .collect()
+    }
+
+    pub fn goodness_of_fit(&self) -> [(&'static str, Option<GoodnessOfFit>); 2] {
+        let distances = self.distances();
+        let ideal = self.ideal();
+        [
+            (
+                "Chi-square",
+                statistics::chi_square_test(&distances, &ideal),
+            ),
+            (
+                "Kolmogorov-Smirnov",
+                statistics::ks_test(&distances, &ideal),
+            ),
+        ]
+    }
+
+    pub fn lines(&self, significance_level: f64) -> Vec<String> {
+        let mut lines = Vec::new();
+        let ideal = self.ideal();
+
+        lines.push("=== Summary ===".to_string());
+        lines.push(format!(
+            "Hash function: {} ({} bits)",
+            self.algorithm.name(),
+            self.algorithm.output_bits()
+        ));
+        match self.summary() {
+            Some(summary) => {
+                match self.sample_seed {
+                    Some(seed) => lines.push(format!(
+                        "Flips: {} of {} input bits ({}, seed {:#x})",
+                        summary.count,
+                        self.input_len * 8,
+                        match self.strata {
+                            Strata::Uniform => "random sample".to_string(),
+                            strata => format!("random sample {}", strata.name()),
+                        },
+                        seed
+                    )),
+                    None => lines.push(format!("Flips: {}", summary.count)),
+                }
+                lines.push(format!("Minimum: {:.2}%", summary.min));
+                lines.push(format!("Maximum: {:.2}%", summary.max));
+                lines.push(format!("Average: {:.2}%", summary.mean));
+                if let (Some(_), Some(interval)) = (self.sample_seed, self.mean_interval()) {
+                    lines.push(format!(
+                        "Mean avalanche {:.0}% CI: [{:.2}%, {:.2}%] (width {:.2}%)",
+                        sampling::CONFIDENCE * 100.0,
+                        interval.low,
+                        interval.high,
+                        interval.width()
+                    ));
+                }
+                lines.push(format!(
+                    "Std deviation: {:.2}% (variance {:.4})",
+                    summary.std_dev, summary.variance
+                ));
+                lines.push(format!(
+                    "Median: {:.2}% (Q1 {:.2}%, Q3 {:.2}%)",
+                    summary.median, summary.q1, summary.q3
+                ));
+                lines.push(format!(
+                    "Percentiles: p1 {:.2}%, p5 {:.2}%, p95 {:.2}%, p99 {:.2}%",
+                    summary.p1, summary.p5, summary.p95, summary.p99
+                ));
+                lines.push(format!(
+                    "Skewness: {:.4}, excess kurtosis: {:.4}",
+                    summary.skewness, summary.kurtosis
+                ));
+
+                lines.push(format!("=== Ideal Binomial({}, 0.5) ===", ideal.trials));
+                lines.push(format!(
+                    "Mean distance: expected {:.2} bits, observed {:.2} bits",
+                    ideal.mean(),
+                    self.to_bits(summary.mean)
+                ));
+                lines.push(format!(
+                    "Std deviation: expected {:.2} bits, observed {:.2} bits",
+                    ideal.std_dev(),
+                    self.to_bits(summary.std_dev)
+                ));
+
+                lines.push(format!(
+                    "=== Goodness of fit (alpha = {}) ===",
+                    significance_level
+                ));
+                for (name, result) in self.goodness_of_fit() {
+                    let line = match result {
+                        Some(fit) => format!(
+                            "{}: statistic {:.4}{}, p-value {:.4} -> {}",
+                            fit.test,
+                            fit.statistic,
+                            fit.degrees_of_freedom
+                                .map_or(String::new(), |df| format!(" (df {})", df)),
+                            fit.p_value,
+                            if fit.passes(significance_level) {
+                                "PASS"
+                            } else {
+                                "FAIL"
+                            }
+                        ),
+                        None => format!("{}: not enough data", name),
+                    };
+                    lines.push(line);
+                }
+            }
+            None => {
+                lines.push("No bits to flip".to_string());
+            }
+        }
+        lines.push(format!("Computation time: {:?}", self.elapsed));
+        lines
     }
 }
 
@@ -68,6 +503,9 @@ mod tests {
         assert!(matches!(app.current_screen, CurrentScreen::Sandbox));
         assert!(matches!(app.current_mode, Some(SandboxMode::Automatic)));
         assert!(matches!(app.input_state, Some(InputState::EnteringText)));
+        // Nothing runs until the input is submitted.
+        assert_eq!(app.messages, vec!["Enter string to hash:"]);
+        assert!(app.last_export.is_none());
     }
 
     #[test]
@@ -166,6 +604,67 @@ mod tests {
         assert!(matches!(app.current_mode, Some(SandboxMode::Automatic)));
     }
 
+    #[test]
+    fn test_process_automatic_reports_selected_algorithm() {
+        let mut app = App::new();
+        app.original_text = "test".to_string();
+        app.algorithm_index = algorithms::ALGORITHMS
+            .iter()
+            .position(|a| a.name() == "BLAKE3")
+            .unwrap();
+
+        app.process_automatic();
+
+        assert!(
+            app.messages
+                .iter()
+                .any(|m| m == "Hash function: BLAKE3 (256 bits)")
+        );
+    }
+
+    #[test]
+    fn test_process_automatic_reports_goodness_of_fit() {
+        let mut app = App::new();
+        app.original_text = "goodness of fit".to_string();
+        app.significance_level = 0.01;
+
+        app.process_automatic();
+
+        assert!(
+            app.messages
+                .iter()
+                .any(|m| m == "=== Goodness of fit (alpha = 0.01) ===")
+        );
+        let chi = app
+            .messages
+            .iter()
+            .find(|m| m.starts_with("Chi-square:"))
+            .unwrap();
+        assert!(chi.contains("p-value"));
+        assert!(chi.ends_with("PASS") || chi.ends_with("FAIL"));
+        assert!(
+            app.messages
+                .iter()
+                .any(|m| m.starts_with("Kolmogorov-Smirnov:"))
+        );
+    }
+
+    #[test]
+    fn test_analyze_collects_one_percentage_per_bit() {
+        let report = analyze(algorithms::ALGORITHMS[0], b"abc");
+        assert_eq!(report.percentages().len(), 24);
+        assert!(
+            report
+                .flips
+                .iter()
+                .enumerate()
+                .all(|(i, flip)| flip.bit_index == i)
+        );
+        assert_eq!(report.distances().len(), 24);
+        assert!(report.summary().is_some());
+        assert!(report.lines(0.05).iter().any(|l| l == "Flips: 24"));
+    }
+
     #[test]
     fn test_process_automatic_empty_string() {
         let mut app = App::new();
@@ -174,5 +673,189 @@ mod tests {
         app.process_automatic();
 
         assert!(app.messages.iter().any(|m| m.contains("=== Summary ===")));
+        assert!(app.messages.iter().any(|m| m == "No bits to flip"));
+    }
+
+    #[test]
+    fn test_process_automatic_reports_distribution() {
+        let mut app = App::new();
+        app.original_text = "test".to_string();
+
+        app.process_automatic();
+
+        assert!(app.messages.iter().any(|m| m == "Flips: 32"));
+        assert!(app.messages.iter().any(|m| m.contains("Std deviation:")));
+        assert!(app.messages.iter().any(|m| m.contains("Median:")));
+        assert!(app.messages.iter().any(|m| m.contains("Percentiles: p1")));
+        assert!(app.messages.iter().any(|m| m.contains("Skewness:")));
+        assert!(
+            app.messages
+                .iter()
+                .any(|m| m == "=== Ideal Binomial(256, 0.5) ===")
+        );
+        assert!(
+            app.messages
+                .iter()
+                .any(|m| m.starts_with("Mean distance: expected 128.00 bits"))
+        );
+    }
+
+    #[test]
+    fn test_analyze_binary_input_matches_reference_flip() {
+        let data = [0x00, 0xff, 0x80];
+        let report = analyze(algorithms::ALGORITHMS[0], &data);
+        assert_eq!(report.flips.len(), 24);
+        assert_eq!(report.input_len, 3);
+
+        // Bit 0 is the least significant bit of the last byte.
+        let expected = algorithms::ALGORITHMS[0].hash(&[0x00, 0xff, 0x81]);
+        assert_eq!(report.flips[0].flipped_digest, expected);
+    }
+
+    #[test]
+    fn test_reused_buffer_flips_one_bit_at_a_time() {
+        let data = b"avalanche";
+        let report = analyze(algorithms::ALGORITHMS[0], data);
+        for flip in &report.flips {
+            let mut expected = data.to_vec();
+            manipulations::flip_bit_in_bytes(&mut expected, flip.bit_index);
+            assert_eq!(
+                flip.flipped_digest,
+                algorithms::ALGORITHMS[0].hash(&expected)
+            );
+            assert_eq!(
+                flip.hamming_distance,
+                diff::HashDiff::new(&report.original_digest, &flip.flipped_digest)
+                    .unwrap()
+                    .hamming_distance()
+            );
+        }
+    }
+
+    #[test]
+    fn test_analyze_sampled_flips_requested_count() {
+        let data = vec![0u8; 1024];
+        let report = analyze_sampled(algorithms::ALGORITHMS[0], &data, 100, 7);
+        assert_eq!(report.flips.len(), 100);
+        assert_eq!(report.sample_seed, Some(7));
+        assert!(
+            report
+                .flips
+                .windows(2)
+                .all(|w| w[0].bit_index < w[1].bit_index)
+        );
+        assert!(
+            report
+                .lines(0.05)
+                .iter()
+                .any(|l| l == "Flips: 100 of 8192 input bits (random sample, seed 0x7)")
+        );
+
+        let again = analyze_sampled(algorithms::ALGORITHMS[0], &data, 100, 7);
+        assert_eq!(report.distances(), again.distances());
+    }
+
+    #[test]
+    fn test_analyze_auto_samples_large_inputs() {
+        let small = analyze_auto(algorithms::ALGORITHMS[0], b"abc", &SamplingPlan::default());
+        assert!(small.sample_seed.is_none());
+
+        let large = vec![0u8; MAX_EXHAUSTIVE_BITS / 8 + 1];
+        let report = analyze_auto(algorithms::ALGORITHMS[0], &large, &SamplingPlan::default());
+        assert_eq!(report.flips.len(), SAMPLED_FLIPS);
+        assert_eq!(report.sample_seed, Some(random::DEFAULT_SEED));
+    }
+
+    #[test]
+    fn test_monte_carlo_stops_at_target_width() {
+        let data = vec![0x42u8; 4096];
+        let plan = SamplingPlan::new(Strata::Bytes(1), Target::Width(2.0));
+        let report = analyze_monte_carlo(algorithms::ALGORITHMS[0], &data, &plan);
+
+        let interval = report.mean_interval().unwrap();
+        assert!(interval.width() <= 2.0);
+        assert!(interval.low < 50.0 && interval.high > 50.0);
+        assert!(report.flips.len() < sampling::MAX_ADAPTIVE_FLIPS);
+        assert!(report.flips.len().is_multiple_of(sampling::BATCH_FLIPS));
+        assert!(
+            report
+                .flips
+                .windows(2)
+                .all(|w| w[0].bit_index < w[1].bit_index)
+        );
+
+        let lines = report.lines(0.05);
+        assert!(lines.iter().any(|l| l.starts_with(&format!(
+            "Flips: {} of 32768 input bits (random sample stratified by byte, seed",
+            report.flips.len()
+        ))));
+        assert!(
+            lines
+                .iter()
+                .any(|l| l.starts_with("Mean avalanche 95% CI: ["))
+        );
+    }
+
+    #[test]
+    fn test_monte_carlo_count_target_matches_analyze_sampled() {
+        let data = vec![7u8; 512];
+        let plan = SamplingPlan {
+            seed: 9,
+            ..SamplingPlan::new(Strata::Uniform, Target::Count(200))
+        };
+        let report = analyze_monte_carlo(algorithms::ALGORITHMS[0], &data, &plan);
+        let sampled = analyze_sampled(algorithms::ALGORITHMS[0], &data, 200, 9);
+        assert_eq!(report.distances(), sampled.distances());
+
+        // A census has no sampling error.
+        let all = SamplingPlan::new(Strata::Bytes(64), Target::Count(usize::MAX));
+        let report = analyze_monte_carlo(algorithms::ALGORITHMS[0], b"abcd", &all);
+        assert_eq!(report.flips.len(), 32);
+        assert_eq!(report.mean_interval().unwrap().width(), 0.0);
+        assert!(
+            !analyze(algorithms::ALGORITHMS[0], b"abcd")
+                .lines(0.05)
+                .iter()
+                .any(|l| l.contains("CI"))
+        );
+    }
+
+    #[test]
+    fn test_weight_sweep_covers_each_weight() {
+        let points = weight_sweep(algorithms::ALGORITHMS[0], b"abcd", 8, 16, 1);
+        assert_eq!(points.len(), 8);
+        for (i, point) in points.iter().enumerate() {
+            assert_eq!(point.weight, i + 1);
+            assert_eq!(point.summary.count, 16);
+            assert!(point.summary.mean > 30.0 && point.summary.mean < 70.0);
+        }
+
+        let again = weight_sweep(algorithms::ALGORITHMS[0], b"abcd", 8, 16, 1);
+        assert_eq!(points[7].summary.mean, again[7].summary.mean);
+    }
+
+    #[test]
+    fn test_weight_sweep_limited_by_input_size() {
+        assert_eq!(
+            weight_sweep(algorithms::ALGORITHMS[0], b"a", 16, 4, 1).len(),
+            8
+        );
+        assert!(weight_sweep(algorithms::ALGORITHMS[0], b"", 16, 4, 1).is_empty());
+        assert!(sweep_lines(&[], 1).is_empty());
+    }
+
+    #[test]
+    fn test_process_automatic_reports_weight_sweep() {
+        let mut app = App::new();
+        app.original_text = "test".to_string();
+
+        app.process_automatic();
+
+        assert!(
+            app.messages
+                .iter()
+                .any(|m| m == "=== Avalanche by input difference weight ===")
+        );
+        assert!(app.messages.iter().any(|m| m.starts_with("k = 16: mean")));
     }
 }
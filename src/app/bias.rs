@@ -0,0 +1,279 @@
+use crate::app::*;
+use automatic::{AutomaticReport, MAX_EXHAUSTIVE_BITS, SAMPLED_FLIPS};
+use rayon::prelude::*;
+use statistics::{OutputBitCounts, normal_quantile};
+
+pub const BIAS_RANDOM_MESSAGES: usize = 15;
+const MAX_LISTED: usize = 8;
+const STRIP_COLUMNS: usize = 64;
+
+pub fn from_report(report: &AutomaticReport) -> OutputBitCounts {
+    let mut counts = OutputBitCounts::new(report.algorithm.output_bits());
+    for flip in &report.flips {
+        counts.add(&report.original_digest, &flip.flipped_digest);
+    }
+    counts
+}
+
+// Flips every input bit (or a sample for large inputs) of `data` and of
+// `random_messages` random messages of the same length.
+pub fn output_bit_counts(
+    algorithm: &'static dyn HashAlgorithm,
+    data: &[u8],
+    random_messages: usize,
+    seed: u64,
+) -> OutputBitCounts {
+    let total_bits = data.len() * 8;
+    let positions = if total_bits > MAX_EXHAUSTIVE_BITS {
+        random::sample_indices(total_bits, SAMPLED_FLIPS, &mut random::Rng::new(seed))
+    } else {
+        (0..total_bits).collect()
+    };
+
+    let output_bits = algorithm.output_bits();
+    (0..=random_messages)
+        .map(|index| {
+            let message = match index {
+                0 => data.to_vec(),
+                _ => random::MessageKind::Uniform.message(data.len(), seed, index),
+            };
+            let original = algorithm.hash(&message);
+            positions
+                .par_iter()
+                .fold(
+                    || (OutputBitCounts::new(output_bits), message.clone()),
+                    |(mut counts, mut buffer), &bit| {
+                        manipulations::flip_bit_in_bytes(&mut buffer, bit);
+                        counts.add(&original, &algorithm.hash(&buffer));
+                        manipulations::flip_bit_in_bytes(&mut buffer, bit);
+                        (counts, buffer)
+                    },
+                )
+                .map(|(counts, _)| counts)
+                .reduce(|| OutputBitCounts::new(output_bits), OutputBitCounts::merge)
+        })
+        .fold(OutputBitCounts::new(output_bits), OutputBitCounts::merge)
+}
+
+pub struct BiasedBit {
+    pub bit: usize,
+    pub rate: f64,
+    pub interval: (f64, f64),
+}
+
+// Output bits whose confidence interval excludes 50%, with the significance
+// level Bonferroni-corrected over all output bits; farthest from 50% first.
+pub fn biased_bits(counts: &OutputBitCounts, significance_level: f64) -> Vec<BiasedBit> {
+    let z = corrected_z(counts, significance_level);
+    let mut biased: Vec<BiasedBit> = (0..counts.changes.len())
+        .map(|bit| BiasedBit {
+            bit,
+            rate: counts.rate(bit),
+            interval: counts.interval(bit, z),
+        })
+        .filter(|b| b.interval.0 > 0.5 || b.interval.1 < 0.5)
+        .collect();
+    biased.sort_by(|a, b| (b.rate - 0.5).abs().total_cmp(&(a.rate - 0.5).abs()));
+    biased
+}
+
+fn corrected_z(counts: &OutputBitCounts, significance_level: f64) -> f64 {
+    let tests = counts.changes.len().max(1) as f64;
+    normal_quantile(1.0 - significance_level / (2.0 * tests))
+}
+
+// `seed` is the one the random messages were drawn from, if there were any.
+pub fn bias_lines(
+    counts: &OutputBitCounts,
+    messages: usize,
+    seed: Option<u64>,
+    significance_level: f64,
+) -> Vec<String> {
+    if counts.trials == 0 {
+        return Vec::new();
+    }
+    let output_bits = counts.changes.len();
+    let rates: Vec<(usize, f64)> = (0..output_bits)
+        .map(|bit| (bit, counts.rate(bit)))
+        .collect();
+    let (min_bit, min) = rates
+        .iter()
+        .copied()
+        .min_by(|a, b| a.1.total_cmp(&b.1))
+        .unwrap_or_default();
+    let (max_bit, max) = rates
+        .iter()
+        .copied()
+        .max_by(|a, b| a.1.total_cmp(&b.1))
+        .unwrap_or_default();
+    let biased = biased_bits(counts, significance_level);
+    let confidence = 100.0 * (1.0 - significance_level / output_bits as f64);
+
+    let mut lines = vec![
+        format!(
+            "=== Output bit bias (alpha = {}, Bonferroni over {} bits) ===",
+            significance_level, output_bits
+        ),
+        format!(
+            "Flips: {} over {} message{}{}",
+            counts.trials,
+            messages,
+            if messages == 1 { "" } else { "s" },
+            seed.map_or(String::new(), |seed| format!(" (seed {:#x})", seed))
+        ),
+        format!(
+            "Change rate per output bit: min {:.2}% (bit {}), max {:.2}% (bit {})",
+            min * 100.0,
+            min_bit,
+            max * 100.0,
+            max_bit
+        ),
+        format!(
+            "Biased output bits: {} of {} -> {}",
+            biased.len(),
+            output_bits,
+            if biased.is_empty() { "PASS" } else { "FAIL" }
+        ),
+    ];
+    for b in biased.iter().take(MAX_LISTED) {
+        lines.push(format!(
+            "  bit {}: {:.2}% changed, {:.2}% CI [{:.2}%, {:.2}%]",
+            b.bit,
+            b.rate * 100.0,
+            confidence,
+            b.interval.0 * 100.0,
+            b.interval.1 * 100.0
+        ));
+    }
+    if biased.len() > MAX_LISTED {
+        lines.push(format!("  ... {} more", biased.len() - MAX_LISTED));
+    }
+    lines
+}
+
+// Output bits in rows of 64: red when biased after correction, yellow when
+// only the uncorrected interval excludes 50%.
+pub fn bias_strip(counts: &OutputBitCounts, significance_level: f64) -> Vec<Vec<ColoredText>> {
+    if counts.trials == 0 {
+        return Vec::new();
+    }
+    let corrected = corrected_z(counts, significance_level);
+    let uncorrected = normal_quantile(1.0 - significance_level / 2.0);
+    let excludes_half = |(low, high): (f64, f64)| low > 0.5 || high < 0.5;
+
+    (0..counts.changes.len())
+        .collect::<Vec<_>>()
+        .chunks(STRIP_COLUMNS)
+        .map(|bits| {
+            let mut row = vec![ColoredText {
+                text: format!("{:>5} | ", bits[0]),
+                color: Color::Gray,
+            }];
+            row.extend(bits.iter().map(|&bit| ColoredText {
+                text: "█".to_string(),
+                color: if excludes_half(counts.interval(bit, corrected)) {
+                    Color::Red
+                } else if excludes_half(counts.interval(bit, uncorrected)) {
+                    Color::Yellow
+                } else {
+                    Color::Green
+                },
+            }));
+            row
+        })
+        .collect()
+}
+
+impl App {
+    pub(crate) fn push_bias(&mut self, counts: &OutputBitCounts, messages: usize) {
+        let lines = bias_lines(counts, messages, None, self.significance_level);
+        if lines.is_empty() {
+            return;
+        }
+        self.messages.extend(lines);
+        for row in bias_strip(counts, self.significance_level) {
+            self.add_colored_text_message(&row);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_from_report_matches_direct_counts() {
+        let algorithm = algorithms::ALGORITHMS[0];
+        let report = automatic::analyze(algorithm, b"abc");
+        let a = from_report(&report);
+        let b = output_bit_counts(algorithm, b"abc", 0, 1);
+        assert_eq!(a.trials, 24);
+        assert_eq!(a.changes, b.changes);
+
+        let total: u64 = a.changes.iter().sum();
+        let distances: usize = report.flips.iter().map(|f| f.hamming_distance).sum();
+        assert_eq!(total as usize, distances);
+    }
+
+    #[test]
+    fn test_random_messages_add_trials() {
+        let counts = output_bit_counts(algorithms::ALGORITHMS[0], b"ab", 3, 9);
+        assert_eq!(counts.trials, 4 * 16);
+        assert_eq!(
+            counts.changes,
+            output_bit_counts(algorithms::ALGORITHMS[0], b"ab", 3, 9).changes
+        );
+    }
+
+    #[test]
+    fn test_sha256_unbiased_fnv_biased() {
+        let sha = output_bit_counts(algorithms::ALGORITHMS[0], b"abcd", 15, 1);
+        assert!(biased_bits(&sha, 0.05).is_empty());
+
+        let fnv = output_bit_counts(algorithms::find("FNV-1a-32").unwrap(), b"abcd", 15, 1);
+        let biased = biased_bits(&fnv, 0.05);
+        assert!(!biased.is_empty());
+        assert!(
+            biased
+                .windows(2)
+                .all(|w| { (w[0].rate - 0.5).abs() >= (w[1].rate - 0.5).abs() })
+        );
+    }
+
+    #[test]
+    fn test_bias_lines_and_strip() {
+        let counts = output_bit_counts(algorithms::find("CRC-8").unwrap(), b"abcd", 15, 1);
+        let lines = bias_lines(&counts, 16, Some(1), 0.05);
+        assert_eq!(
+            lines[0],
+            "=== Output bit bias (alpha = 0.05, Bonferroni over 8 bits) ==="
+        );
+        assert_eq!(lines[1], "Flips: 512 over 16 messages (seed 0x1)");
+
+        let strip = bias_strip(&counts, 0.05);
+        assert_eq!(strip.len(), 1);
+        assert_eq!(strip[0].len(), 1 + 8);
+
+        let sha = bias_strip(
+            &from_report(&automatic::analyze(algorithms::ALGORITHMS[0], b"a")),
+            0.05,
+        );
+        assert_eq!(sha.len(), 4);
+        assert_eq!(sha[3][0].text, "  192 | ");
+    }
+
+    #[test]
+    fn test_process_automatic_includes_bias() {
+        let mut app = App::new();
+        app.original_text = "hey".to_string();
+
+        app.process_automatic();
+
+        assert!(
+            app.messages
+                .iter()
+                .any(|m| m.starts_with("=== Output bit bias"))
+        );
+        assert!(app.messages.iter().any(|m| m == "Flips: 24 over 1 message"));
+    }
+}
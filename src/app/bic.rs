@@ -0,0 +1,166 @@
+use crate::app::*;
+use sac::{MAX_MATRIX_MESSAGE_LEN, heatmap_color};
+use statistics::BicMatrix;
+
+pub const BIC_SAMPLES: usize = 1024;
+const HEATMAP_COLUMNS: usize = 64;
+
+impl App {
+    pub fn switch_to_bic(&mut self) {
+        self.current_screen = CurrentScreen::Sandbox;
+        self.current_mode = Some(SandboxMode::Bic);
+        self.input_state = Some(InputState::EnteringText);
+        self.messages.clear();
+        self.colored_messages.clear();
+        self.messages.push("Enter string to hash:".to_string());
+    }
+
+    pub fn process_bic(&mut self) {
+        if self.message().len() > MAX_MATRIX_MESSAGE_LEN {
+            self.messages.push(format!(
+                "Error: BIC analysis supports messages up to {} bytes, got {}",
+                MAX_MATRIX_MESSAGE_LEN,
+                self.message().len()
+            ));
+            self.messages.push("Press Enter to continue...".to_string());
+            return;
+        }
+        let algorithm = self.algorithm();
+        let time = std::time::Instant::now();
+        let bic = BicMatrix::compute_with_progress(
+            algorithm,
+            self.message().len(),
+            BIC_SAMPLES,
+            self.seed,
+            self.message_kind,
+            &self.progress,
+        );
+        let elapsed = time.elapsed();
+        if self.progress.is_cancelled() {
+            return;
+        }
+
+        self.messages
+            .push("=== Bit Independence Criterion ===".to_string());
+        self.messages
+            .push(format!("Hash function: {}", algorithm.name()));
+        self.messages.push(format!(
+            "Correlation matrix: {} x {} output bits over {} input bit flips",
+            bic.output_bits, bic.output_bits, bic.input_bits
+        ));
+        self.messages.push(format!(
+            "Random messages: {} {} (seed {:#x})",
+            bic.samples,
+            bic.kind.name(),
+            bic.seed
+        ));
+        self.messages.push(format!(
+            "Mean |correlation|: {:.4}",
+            bic.mean_abs_correlation()
+        ));
+        if let Some((j, k, correlation)) = bic.max_abs_correlation() {
+            self.messages.push(format!(
+                "Max |correlation|: {:.4} (output bits {} and {})",
+                correlation.abs(),
+                j,
+                k
+            ));
+        }
+        self.messages.push(format!(
+            "Expected noise (1 sigma): {:.4}",
+            bic.expected_noise()
+        ));
+        self.messages
+            .push(format!("Computation time: {:?}", elapsed));
+
+        if bic.input_bits > 0 {
+            self.messages.push("".to_string());
+            self.messages.push(
+                "Heatmap (output bits x output bits, green < 4 sigma < yellow < 6 sigma < red):"
+                    .to_string(),
+            );
+            let group = bic.output_bits.div_ceil(HEATMAP_COLUMNS).max(1);
+            for start in (0..bic.output_bits).step_by(group) {
+                let row = bic_heatmap_row(&bic, start, group);
+                self.add_colored_text_message(&row);
+            }
+        }
+        self.messages.push("Press Enter to continue...".to_string());
+    }
+}
+
+fn bic_heatmap_row(bic: &BicMatrix, row_start: usize, group: usize) -> Vec<ColoredText> {
+    let sigma = bic.expected_noise();
+    let row_end = (row_start + group).min(bic.output_bits);
+
+    let mut row = vec![ColoredText {
+        text: format!("{:>5} ", row_start),
+        color: Color::Gray,
+    }];
+    for col_start in (0..bic.output_bits).step_by(group) {
+        let col_end = (col_start + group).min(bic.output_bits);
+        let worst = (row_start..row_end)
+            .flat_map(|j| (col_start..col_end).map(move |k| (j, k)))
+            .filter(|(j, k)| j != k)
+            .map(|(j, k)| bic.correlation(j, k).abs())
+            .fold(0.0, f64::max);
+        row.push(ColoredText {
+            text: "█".to_string(),
+            color: heatmap_color(worst, 4.0 * sigma, 6.0 * sigma),
+        });
+    }
+    row
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_switch_to_bic() {
+        let mut app = App::new();
+        app.switch_to_bic();
+
+        assert!(matches!(app.current_screen, CurrentScreen::Sandbox));
+        assert!(matches!(app.current_mode, Some(SandboxMode::Bic)));
+        assert!(matches!(app.input_state, Some(InputState::EnteringText)));
+    }
+
+    #[test]
+    fn test_process_bic_reports_summary_and_heatmap() {
+        let mut app = App::new();
+        app.original_text = "a".to_string();
+
+        app.process_bic();
+
+        assert!(app.messages.iter().any(|m| m.contains("Max |correlation|")));
+        assert_eq!(app.colored_messages.len(), 64);
+        assert_eq!(app.colored_messages[0].len(), 1 + 64);
+    }
+
+    #[test]
+    fn test_process_bic_empty_string() {
+        let mut app = App::new();
+        app.original_text = "".to_string();
+
+        app.process_bic();
+
+        assert!(app.colored_messages.is_empty());
+        assert!(!app.messages.iter().any(|m| m.contains("Max |correlation|")));
+    }
+
+    #[test]
+    fn test_process_bic_rejects_long_messages() {
+        let mut app = App::new();
+        app.input_bytes = Some(vec![0; MAX_MATRIX_MESSAGE_LEN + 1]);
+
+        app.process_bic();
+
+        assert!(
+            app.messages
+                .iter()
+                .any(|m| m.starts_with("Error: BIC analysis supports messages up to"))
+        );
+        assert!(app.colored_messages.is_empty());
+    }
+}
@@ -0,0 +1,185 @@
+// Error-detecting codes, included as anti-examples: CRCs are affine over
+// GF(2), so the output difference of a flip never depends on the message.
+
+pub struct CrcParams {
+    pub width: u32,
+    pub poly: u64,
+    pub init: u64,
+    // Reflected input and output, as in the usual catalogue entries.
+    pub reflect: bool,
+    pub xor_out: u64,
+}
+
+pub const CRC_8: CrcParams = CrcParams {
+    width: 8,
+    poly: 0x07,
+    init: 0,
+    reflect: false,
+    xor_out: 0,
+};
+
+pub const CRC_16_ARC: CrcParams = CrcParams {
+    width: 16,
+    poly: 0x8005,
+    init: 0,
+    reflect: true,
+    xor_out: 0,
+};
+
+pub const CRC_16_CCITT: CrcParams = CrcParams {
+    width: 16,
+    poly: 0x1021,
+    init: 0xffff,
+    reflect: false,
+    xor_out: 0,
+};
+
+pub const CRC_32: CrcParams = CrcParams {
+    width: 32,
+    poly: 0x04c11db7,
+    init: 0xffffffff,
+    reflect: true,
+    xor_out: 0xffffffff,
+};
+
+pub const CRC_32C: CrcParams = CrcParams {
+    width: 32,
+    poly: 0x1edc6f41,
+    init: 0xffffffff,
+    reflect: true,
+    xor_out: 0xffffffff,
+};
+
+pub const CRC_64_ECMA: CrcParams = CrcParams {
+    width: 64,
+    poly: 0x42f0e1eba9ea3693,
+    init: 0,
+    reflect: false,
+    xor_out: 0,
+};
+
+pub const CRC_64_XZ: CrcParams = CrcParams {
+    width: 64,
+    poly: 0x42f0e1eba9ea3693,
+    init: u64::MAX,
+    reflect: true,
+    xor_out: u64::MAX,
+};
+
+// Bit-at-a-time CRC; slow, but short enough to read alongside the tables.
+pub fn crc(params: &CrcParams, data: &[u8]) -> u64 {
+    let mask = u64::MAX >> (64 - params.width);
+    let mut crc = params.init;
+
+    if params.reflect {
+        let poly = params.poly.reverse_bits() >> (64 - params.width);
+        crc = crc.reverse_bits() >> (64 - params.width);
+        for &byte in data {
+            crc ^= byte as u64;
+            for _ in 0..8 {
+                crc = if crc & 1 == 1 {
+                    (crc >> 1) ^ poly
+                } else {
+                    crc >> 1
+                };
+            }
+        }
+    } else {
+        let top = 1u64 << (params.width - 1);
+        for &byte in data {
+            crc ^= (byte as u64) << (params.width - 8);
+            for _ in 0..8 {
+                crc = if crc & top != 0 {
+                    (crc << 1) ^ params.poly
+                } else {
+                    crc << 1
+                };
+            }
+            crc &= mask;
+        }
+    }
+    (crc ^ params.xor_out) & mask
+}
+
+pub fn crc_8(data: &[u8]) -> u128 {
+    crc(&CRC_8, data) as u128
+}
+
+pub fn crc_16_arc(data: &[u8]) -> u128 {
+    crc(&CRC_16_ARC, data) as u128
+}
+
+pub fn crc_16_ccitt(data: &[u8]) -> u128 {
+    crc(&CRC_16_CCITT, data) as u128
+}
+
+pub fn crc_32(data: &[u8]) -> u128 {
+    crc(&CRC_32, data) as u128
+}
+
+pub fn crc_32c(data: &[u8]) -> u128 {
+    crc(&CRC_32C, data) as u128
+}
+
+pub fn crc_64_ecma(data: &[u8]) -> u128 {
+    crc(&CRC_64_ECMA, data) as u128
+}
+
+pub fn crc_64_xz(data: &[u8]) -> u128 {
+    crc(&CRC_64_XZ, data) as u128
+}
+
+pub fn adler_32(data: &[u8]) -> u128 {
+    let (mut a, mut b) = (1u32, 0u32);
+    for &byte in data {
+        a = (a + byte as u32) % 65521;
+        b = (b + a) % 65521;
+    }
+    ((b << 16) | a) as u128
+}
+
+pub fn fletcher_16(data: &[u8]) -> u128 {
+    let (mut a, mut b) = (0u16, 0u16);
+    for &byte in data {
+        a = (a + byte as u16) % 255;
+        b = (b + a) % 255;
+    }
+    ((b << 8) | a) as u128
+}
+
+// Sums little-endian 16-bit words; an odd trailing byte is zero-extended.
+pub fn fletcher_32(data: &[u8]) -> u128 {
+    let (mut a, mut b) = (0u32, 0u32);
+    for word in data.chunks(2) {
+        let value = word[0] as u32 | (*word.get(1).unwrap_or(&0) as u32) << 8;
+        a = (a + value) % 65535;
+        b = (b + a) % 65535;
+    }
+    ((b << 16) | a) as u128
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const CHECK: &[u8] = b"123456789";
+
+    #[test]
+    fn test_crc_check_values() {
+        assert_eq!(crc(&CRC_8, CHECK), 0xf4);
+        assert_eq!(crc(&CRC_16_ARC, CHECK), 0xbb3d);
+        assert_eq!(crc(&CRC_16_CCITT, CHECK), 0x29b1);
+        assert_eq!(crc(&CRC_32, CHECK), 0xcbf43926);
+        assert_eq!(crc(&CRC_32C, CHECK), 0xe3069283);
+        assert_eq!(crc(&CRC_64_ECMA, CHECK), 0x6c40df5f0b497347);
+        assert_eq!(crc(&CRC_64_XZ, CHECK), 0x995dc9bbdf1939fa);
+    }
+
+    #[test]
+    fn test_sum_check_values() {
+        assert_eq!(adler_32(b"Wikipedia"), 0x11e60398);
+        assert_eq!(fletcher_16(b"abcde"), 0xc8f0);
+        assert_eq!(fletcher_32(b"abcde"), 0xf04fc729);
+        assert_eq!(fletcher_32(b"abcdef"), 0x56502d2a);
+    }
+}
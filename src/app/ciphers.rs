@@ -0,0 +1,274 @@
+use crate::app::algorithms::HashAlgorithm;
+use aes::cipher::{BlockEncrypt, KeyInit};
+
+// Key and plaintext held constant while the other input is flipped: the
+// FIPS-197 appendix C examples. ChaCha20 shares the 32-byte key.
+pub const FIXED_KEY: [u8; 32] = {
+    let mut key = [0u8; 32];
+    let mut i = 0;
+    while i < 32 {
+        key[i] = i as u8;
+        i += 1;
+    }
+    key
+};
+
+pub const FIXED_PLAINTEXT: [u8; 16] = {
+    let mut block = [0u8; 16];
+    let mut i = 0;
+    while i < 16 {
+        block[i] = (i % 16) as u8 * 0x11;
+        i += 1;
+    }
+    block
+};
+
+// RFC 8439 section 2.3.2 block counter 1, little-endian, then the nonce.
+pub const CHACHA20_COUNTER_NONCE: [u8; 16] = [1, 0, 0, 0, 0, 0, 0, 0x09, 0, 0, 0, 0x4a, 0, 0, 0, 0];
+const CHACHA20_CONSTANTS: [u32; 4] = [0x61707865, 0x3320646e, 0x79622d32, 0x6b206574];
+
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum Cipher {
+    Aes128,
+    Aes256,
+    ChaCha20,
+}
+
+impl Cipher {
+    pub fn key_len(self) -> usize {
+        match self {
+            Cipher::Aes128 => 16,
+            Cipher::Aes256 | Cipher::ChaCha20 => 32,
+        }
+    }
+
+    // The input besides the key: a plaintext block for AES, the block
+    // counter and nonce for the ChaCha20 block function.
+    pub fn block_len(self) -> usize {
+        16
+    }
+
+    pub fn output_len(self) -> usize {
+        match self {
+            Cipher::Aes128 | Cipher::Aes256 => 16,
+            Cipher::ChaCha20 => 64,
+        }
+    }
+
+    pub fn fixed_block(self) -> &'static [u8] {
+        match self {
+            Cipher::Aes128 | Cipher::Aes256 => &FIXED_PLAINTEXT,
+            Cipher::ChaCha20 => &CHACHA20_COUNTER_NONCE,
+        }
+    }
+
+    // Encrypts a single block, or runs the ChaCha20 block function; `key` and
+    // `block` must have the cipher's lengths.
+    pub fn encrypt(self, key: &[u8], block: &[u8]) -> Vec<u8> {
+        let mut output = block.to_vec();
+        match self {
+            Cipher::Aes128 => {
+                aes::Aes128::new(key.into()).encrypt_block(output.as_mut_slice().into())
+            }
+            Cipher::Aes256 => {
+                aes::Aes256::new(key.into()).encrypt_block(output.as_mut_slice().into())
+            }
+            Cipher::ChaCha20 => return chacha20_block(key, block),
+        }
+        output
+    }
+}
+
+// RFC 8439 section 2.3: twenty rounds over the constants, key, counter and
+// nonce, with the input state added back in. Written out because the
+// chacha20 crate refuses the last counter value, which a flip can reach.
+fn chacha20_block(key: &[u8], counter_nonce: &[u8]) -> Vec<u8> {
+    let mut input = [0u32; 16];
+    input[..4].copy_from_slice(&CHACHA20_CONSTANTS);
+    for (word, bytes) in input[4..]
+        .iter_mut()
+        .zip(key.chunks_exact(4).chain(counter_nonce.chunks_exact(4)))
+    {
+        *word = u32::from_le_bytes(bytes.try_into().unwrap());
+    }
+
+    let mut state = input;
+    for _ in 0..10 {
+        quarter_round(&mut state, 0, 4, 8, 12);
+        quarter_round(&mut state, 1, 5, 9, 13);
+        quarter_round(&mut state, 2, 6, 10, 14);
+        quarter_round(&mut state, 3, 7, 11, 15);
+        quarter_round(&mut state, 0, 5, 10, 15);
+        quarter_round(&mut state, 1, 6, 11, 12);
+        quarter_round(&mut state, 2, 7, 8, 13);
+        quarter_round(&mut state, 3, 4, 9, 14);
+    }
+    state
+        .iter()
+        .zip(&input)
+        .flat_map(|(word, initial)| word.wrapping_add(*initial).to_le_bytes())
+        .collect()
+}
+
+fn quarter_round(state: &mut [u32; 16], a: usize, b: usize, c: usize, d: usize) {
+    state[a] = state[a].wrapping_add(state[b]);
+    state[d] = (state[d] ^ state[a]).rotate_left(16);
+    state[c] = state[c].wrapping_add(state[d]);
+    state[b] = (state[b] ^ state[c]).rotate_left(12);
+    state[a] = state[a].wrapping_add(state[b]);
+    state[d] = (state[d] ^ state[a]).rotate_left(8);
+    state[c] = state[c].wrapping_add(state[d]);
+    state[b] = (state[b] ^ state[c]).rotate_left(7);
+}
+
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum FlippedInput {
+    Plaintext,
+    Key,
+}
+
+// A cipher seen as a function of either its plaintext or its key, so it can
+// go through the same analyses as the hash functions.
+pub struct CipherTarget {
+    pub cipher: Cipher,
+    pub input: FlippedInput,
+    name: &'static str,
+}
+
+impl CipherTarget {
+    pub const fn new(cipher: Cipher, input: FlippedInput, name: &'static str) -> Self {
+        CipherTarget {
+            cipher,
+            input,
+            name,
+        }
+    }
+}
+
+impl HashAlgorithm for CipherTarget {
+    fn name(&self) -> &'static str {
+        self.name
+    }
+
+    fn output_bits(&self) -> usize {
+        self.cipher.output_len() * 8
+    }
+
+    fn input_len(&self) -> Option<usize> {
+        Some(match self.input {
+            FlippedInput::Plaintext => self.cipher.block_len(),
+            FlippedInput::Key => self.cipher.key_len(),
+        })
+    }
+
+    fn hash(&self, data: &[u8]) -> Vec<u8> {
+        let key_len = self.cipher.key_len();
+        let block_len = self.cipher.block_len();
+        match self.input {
+            FlippedInput::Plaintext => self
+                .cipher
+                .encrypt(&FIXED_KEY[..key_len], &left_pad(data, block_len)),
+            FlippedInput::Key => self
+                .cipher
+                .encrypt(&left_pad(data, key_len), self.cipher.fixed_block()),
+        }
+    }
+}
+
+// Zero-pads on the left so bit indices, counted from the end of the input,
+// address the same bits of the key or block. Longer inputs keep their tail.
+fn left_pad(data: &[u8], len: usize) -> Vec<u8> {
+    let mut padded = vec![0u8; len];
+    let taken = data.len().min(len);
+    padded[len - taken..].copy_from_slice(&data[data.len() - taken..]);
+    padded
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::app::{
+        algorithms,
+        manipulations::{self, bytes_to_hex},
+    };
+
+    fn target(name: &str) -> &'static dyn HashAlgorithm {
+        algorithms::find(name).unwrap()
+    }
+
+    #[test]
+    fn test_aes_fips197_vectors() {
+        assert_eq!(
+            bytes_to_hex(&target("AES-128").hash(&FIXED_PLAINTEXT[..16])),
+            "69c4e0d86a7b0430d8cdb78070b4c55a"
+        );
+        assert_eq!(
+            bytes_to_hex(&target("AES-256").hash(&FIXED_PLAINTEXT[..16])),
+            "8ea2b7ca516745bfeafc49904b496089"
+        );
+        assert_eq!(
+            target("AES-128-key").hash(&FIXED_KEY[..16]),
+            target("AES-128").hash(&FIXED_PLAINTEXT[..16])
+        );
+        assert_eq!(
+            target("AES-256-key").hash(&FIXED_KEY),
+            target("AES-256").hash(&FIXED_PLAINTEXT[..16])
+        );
+    }
+
+    #[test]
+    fn test_chacha20_rfc8439_block() {
+        let block = target("ChaCha20").hash(&CHACHA20_COUNTER_NONCE);
+        assert_eq!(
+            bytes_to_hex(&block),
+            "10f1e7e4d13b5915500fdd1fa32071c4c7d1f4c733c068030422aa9ac3d46c4e\
+             d2826446079faa0914c2d705d98b02a2b5129cd1de164eb9cbd083e8a2503c4e"
+        );
+        assert_eq!(target("ChaCha20-key").hash(&FIXED_KEY), block);
+
+        // The last counter value is a valid input too.
+        let mut last = CHACHA20_COUNTER_NONCE;
+        last[..4].copy_from_slice(&[0xff; 4]);
+        assert_eq!(target("ChaCha20").hash(&last).len(), 64);
+    }
+
+    #[test]
+    fn test_short_input_is_left_padded() {
+        let aes = target("AES-128");
+        let mut block = [0u8; 16];
+        block[14..].copy_from_slice(b"hi");
+        assert_eq!(aes.hash(b"hi"), aes.hash(&block));
+        assert_eq!(aes.input_len(), Some(16));
+        assert_eq!(target("ChaCha20-key").input_len(), Some(32));
+    }
+
+    #[test]
+    fn test_chacha20_single_flip_changes_half_the_block() {
+        for (name, input) in [
+            ("ChaCha20", &CHACHA20_COUNTER_NONCE[..]),
+            ("ChaCha20-key", &FIXED_KEY[..]),
+        ] {
+            let chacha = target(name);
+            let original = chacha.hash(input);
+            let distances: Vec<u32> = (0..input.len() * 8)
+                .map(|bit| {
+                    let mut flipped = input.to_vec();
+                    manipulations::flip_bit_in_bytes(&mut flipped, bit);
+                    original
+                        .iter()
+                        .zip(chacha.hash(&flipped))
+                        .map(|(a, b)| (a ^ b).count_ones())
+                        .sum()
+                })
+                .collect();
+            // 512 output bits: 256 expected, with a standard deviation of 11.3.
+            assert!(
+                distances.iter().all(|d| (192..=320).contains(d)),
+                "{}",
+                name
+            );
+            let mean = distances.iter().sum::<u32>() as f64 / distances.len() as f64;
+            assert!((mean - 256.0).abs() < 8.0, "{} mean {}", name, mean);
+        }
+    }
+}
@@ -0,0 +1,427 @@
+use crate::app::*;
+use automatic::AutomaticReport;
+use sampling::SamplingPlan;
+use statistics::PairedTest;
+use worker::Progress;
+
+pub const DEFAULT_COMPARED: [&str; 4] = ["SHA-256", "BLAKE3", "MD5", "FNV-1a-32"];
+
+pub struct PairedResult {
+    // Indices into `Comparison::reports`.
+    pub first: usize,
+    pub second: usize,
+    pub test: Option<PairedTest>,
+}
+
+pub struct Comparison {
+    // One report per algorithm, all over the same flipped bit positions.
+    pub reports: Vec<AutomaticReport>,
+    // Algorithms left out, with the reason.
+    pub skipped: Vec<(&'static str, String)>,
+    pub pairs: Vec<PairedResult>,
+}
+
+impl Comparison {
+    pub fn flips(&self) -> usize {
+        self.reports.first().map_or(0, |report| report.flips.len())
+    }
+
+    // Significance level per pair, Bonferroni-corrected over all pairs.
+    pub fn pair_level(&self, significance_level: f64) -> f64 {
+        significance_level / self.pairs.len().max(1) as f64
+    }
+}
+
+// The first usable algorithm picks the bit positions, exhaustively or
+// according to `plan`; every other one flips exactly those.
+pub fn compare(
+    algorithms: &[&'static dyn HashAlgorithm],
+    data: &[u8],
+    plan: &SamplingPlan,
+    progress: &Progress,
+) -> Comparison {
+    let mut reports: Vec<AutomaticReport> = Vec::new();
+    let mut skipped = Vec::new();
+    for &algorithm in algorithms {
+        if let Err(err) = algorithms::check_input(algorithm, data) {
+            skipped.push((algorithm.name(), err));
+            continue;
+        }
+        let report = match reports.first() {
+            None => automatic::analyze_auto_with_progress(algorithm, data, plan, progress),
+            Some(reference) => automatic::analyze_same_flips(algorithm, data, reference, progress),
+        };
+        if progress.is_cancelled() {
+            break;
+        }
+        reports.push(report);
+    }
+
+    let mut pairs = Vec::new();
+    for first in 0..reports.len() {
+        for second in first + 1..reports.len() {
+            pairs.push(PairedResult {
+                first,
+                second,
+                test: statistics::wilcoxon_signed_rank(
+                    &reports[first].percentages(),
+                    &reports[second].percentages(),
+                ),
+            });
+        }
+    }
+    Comparison {
+        reports,
+        skipped,
+        pairs,
+    }
+}
+
+// Flips hashed per second.
+pub fn throughput(report: &AutomaticReport) -> f64 {
+    let seconds = report.elapsed.as_secs_f64();
+    if seconds == 0.0 {
+        return 0.0;
+    }
+    report.flips.len() as f64 / seconds
+}
+
+pub fn format_rate(per_second: f64) -> String {
+    match per_second {
+        r if r >= 1e6 => format!("{:.2}M/s", r / 1e6),
+        r if r >= 1e3 => format!("{:.1}k/s", r / 1e3),
+        r => format!("{:.0}/s", r),
+    }
+}
+
+// Chi-square and Kolmogorov-Smirnov p-values, "-" without enough data.
+pub fn p_value_cells(report: &AutomaticReport) -> [Option<f64>; 2] {
+    report
+        .goodness_of_fit()
+        .map(|(_, fit)| fit.map(|fit| fit.p_value))
+}
+
+pub fn heading(comparison: &Comparison) -> String {
+    let Some(first) = comparison.reports.first() else {
+        return "No hash function could take this input".to_string();
+    };
+    match first.sample_seed {
+        Some(seed) => format!(
+            "Same {} flips of {} input bits for every hash function (seed {:#x})",
+            comparison.flips(),
+            first.input_len * 8,
+            seed
+        ),
+        None => format!("Same {} flips for every hash function", comparison.flips()),
+    }
+}
+
+pub fn paired_lines(comparison: &Comparison, significance_level: f64) -> Vec<String> {
+    if comparison.pairs.is_empty() {
+        return Vec::new();
+    }
+    let level = comparison.pair_level(significance_level);
+    let mut lines = vec![format!(
+        "=== Wilcoxon signed-rank, paired by flipped bit (alpha = {}, Bonferroni over {} pair{}) ===",
+        significance_level,
+        comparison.pairs.len(),
+        if comparison.pairs.len() == 1 { "" } else { "s" }
+    )];
+    for pair in &comparison.pairs {
+        let names = format!(
+            "{} vs {}",
+            comparison.reports[pair.first].algorithm.name(),
+            comparison.reports[pair.second].algorithm.name()
+        );
+        lines.push(match &pair.test {
+            Some(test) => format!(
+                "{}: mean difference {:+.2} points, z {:.2}, p-value {:.4} -> {}",
+                names,
+                test.mean_difference,
+                test.z,
+                test.p_value,
+                if test.p_value < level {
+                    "DIFFERENT"
+                } else {
+                    "no difference"
+                }
+            ),
+            None => format!("{}: not enough data", names),
+        });
+    }
+    lines
+}
+
+pub fn table_header() -> String {
+    format!(
+        "{:<14} {:>5} {:>8} {:>8} {:>8} {:>8} {:>10} {:>10} {:>10}",
+        "Hash", "Bits", "Mean %", "Std %", "Min %", "Max %", "Chi2 p", "KS p", "Flips"
+    )
+}
+
+// One row per report, then the skipped algorithms. The p-value cells are
+// green when the fit test passes and red when it fails.
+pub fn table_rows(comparison: &Comparison, significance_level: f64) -> Vec<Vec<ColoredText>> {
+    let gray = |text: String| ColoredText {
+        text,
+        color: Color::Gray,
+    };
+    let mut rows = Vec::new();
+    for report in &comparison.reports {
+        let Some(summary) = report.summary() else {
+            rows.push(vec![gray(format!(
+                "{:<14} no bits to flip",
+                report.algorithm.name()
+            ))]);
+            continue;
+        };
+        let mut row = vec![gray(format!(
+            "{:<14} {:>5} {:>8.2} {:>8.2} {:>8.2} {:>8.2}",
+            report.algorithm.name(),
+            report.algorithm.output_bits(),
+            summary.mean,
+            summary.std_dev,
+            summary.min,
+            summary.max
+        ))];
+        for p_value in p_value_cells(report) {
+            row.push(gray(" ".to_string()));
+            row.push(match p_value {
+                Some(p_value) => ColoredText {
+                    text: format!("{:>10.4}", p_value),
+                    color: if p_value >= significance_level {
+                        Color::Green
+                    } else {
+                        Color::Red
+                    },
+                },
+                None => gray(format!("{:>10}", "-")),
+            });
+        }
+        row.push(gray(format!(" {:>10}", format_rate(throughput(report)))));
+        rows.push(row);
+    }
+    for (name, err) in &comparison.skipped {
+        rows.push(vec![gray(format!("{:<14} {}", name, err))]);
+    }
+    rows
+}
+
+pub fn comparison_lines(comparison: &Comparison, significance_level: f64) -> Vec<String> {
+    let mut lines = vec![
+        "=== Comparison ===".to_string(),
+        heading(comparison),
+        table_header(),
+    ];
+    lines.extend(
+        table_rows(comparison, significance_level)
+            .iter()
+            .map(|row| {
+                row.iter()
+                    .map(|cell| cell.text.as_str())
+                    .collect::<String>()
+            }),
+    );
+    lines.extend(paired_lines(comparison, significance_level));
+    lines
+}
+
+impl App {
+    pub fn switch_to_compare(&mut self) {
+        self.current_screen = CurrentScreen::Sandbox;
+        self.current_mode = Some(SandboxMode::Compare);
+        self.input_state = Some(InputState::EnteringText);
+        self.messages.clear();
+        self.colored_messages.clear();
+        self.comparison = None;
+        self.messages.push(format!(
+            "Comparing {} (k in the menu adds or removes the selected hash)",
+            self.compared_names().join(", ")
+        ));
+        self.messages.push("Enter string to hash:".to_string());
+    }
+
+    pub fn compared_names(&self) -> Vec<&'static str> {
+        self.compared
+            .iter()
+            .map(|&index| algorithms::ALGORITHMS[index].name())
+            .collect()
+    }
+
+    // Adds the hash selected with ←→ to the comparison, or removes it.
+    pub fn toggle_compared(&mut self) {
+        match self
+            .compared
+            .iter()
+            .position(|&index| index == self.algorithm_index)
+        {
+            Some(position) => {
+                self.compared.remove(position);
+            }
+            None => self.compared.push(self.algorithm_index),
+        }
+    }
+
+    pub fn comparison_active(&self) -> bool {
+        matches!(self.current_mode, Some(SandboxMode::Compare))
+            && matches!(self.input_state, Some(InputState::ShowingResult))
+            && self.comparison.is_some()
+    }
+
+    pub fn process_compare(&mut self) {
+        if self.compared.len() < 2 {
+            self.messages.push(
+                "Error: select at least two hash functions, with ←→ and k in the menu".to_string(),
+            );
+            self.messages.push("Press Enter to continue...".to_string());
+            return;
+        }
+        let selected: Vec<&'static dyn HashAlgorithm> = self
+            .compared
+            .iter()
+            .map(|&index| algorithms::ALGORITHMS[index])
+            .collect();
+        let comparison = compare(
+            &selected,
+            self.message(),
+            &SamplingPlan {
+                seed: self.seed,
+                ..self.sampling
+            },
+            &self.progress,
+        );
+        if self.progress.is_cancelled() {
+            return;
+        }
+        self.messages
+            .extend(comparison_lines(&comparison, self.significance_level));
+        self.messages.push("Press Enter to continue...".to_string());
+        self.comparison = Some(comparison);
+    }
+}
+
+pub fn default_compared() -> Vec<usize> {
+    DEFAULT_COMPARED
+        .iter()
+        .filter_map(|name| {
+            algorithms::ALGORITHMS
+                .iter()
+                .position(|algorithm| algorithm.name() == *name)
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn named(names: &[&str]) -> Vec<&'static dyn HashAlgorithm> {
+        names
+            .iter()
+            .map(|name| algorithms::find(name).unwrap())
+            .collect()
+    }
+
+    #[test]
+    fn test_every_algorithm_flips_the_same_bits() {
+        let data = vec![0x11u8; 9000];
+        let comparison = compare(
+            &named(&["SHA-256", "MD5", "FNV-1a-32"]),
+            &data,
+            &SamplingPlan::default(),
+            &Progress::none(),
+        );
+        assert_eq!(comparison.reports.len(), 3);
+        assert_eq!(comparison.flips(), automatic::SAMPLED_FLIPS);
+        let positions = |report: &AutomaticReport| -> Vec<usize> {
+            report.flips.iter().map(|flip| flip.bit_index).collect()
+        };
+        for report in &comparison.reports[1..] {
+            assert_eq!(positions(report), positions(&comparison.reports[0]));
+            assert_eq!(report.sample_seed, Some(random::DEFAULT_SEED));
+        }
+        assert_eq!(comparison.pairs.len(), 3);
+        assert_eq!(comparison.pair_level(0.06), 0.02);
+    }
+
+    #[test]
+    fn test_paired_test_separates_weak_hash() {
+        let comparison = compare(
+            &named(&["SHA-256", "BLAKE3", "FNV-1a-32"]),
+            b"the same input for everyone",
+            &SamplingPlan::default(),
+            &Progress::none(),
+        );
+        let lines = paired_lines(&comparison, 0.05);
+        assert_eq!(
+            lines[0],
+            "=== Wilcoxon signed-rank, paired by flipped bit (alpha = 0.05, Bonferroni over 3 pairs) ==="
+        );
+        assert!(lines[1].starts_with("SHA-256 vs BLAKE3:"));
+        assert!(lines[1].ends_with("no difference"));
+        assert!(lines[2].starts_with("SHA-256 vs FNV-1a-32:"));
+        assert!(lines[2].ends_with("DIFFERENT"));
+    }
+
+    #[test]
+    fn test_comparison_lines_list_skipped_algorithms() {
+        let comparison = compare(
+            &named(&["SHA-256", "AES-128", "MD5"]),
+            b"a message longer than one block",
+            &SamplingPlan::default(),
+            &Progress::none(),
+        );
+        let lines = comparison_lines(&comparison, 0.05);
+        assert_eq!(lines[1], "Same 248 flips for every hash function");
+        assert!(lines[3].starts_with("SHA-256"));
+        assert!(lines[3].ends_with("/s"));
+        assert!(lines[4].starts_with("MD5"));
+        assert!(lines[5].ends_with("AES-128 takes at most 16 bytes of input, got 31"));
+        assert!(lines[7].starts_with("SHA-256 vs MD5:"));
+
+        let rows = table_rows(&comparison, 0.05);
+        assert_eq!(rows.len(), 3);
+        let p_value = &rows[0][2];
+        assert!(p_value.text.trim().parse::<f64>().is_ok());
+        assert_ne!(p_value.color, Color::Gray);
+    }
+
+    #[test]
+    fn test_toggle_compared_and_process() {
+        let mut app = App::new();
+        assert_eq!(app.compared_names(), DEFAULT_COMPARED);
+        app.algorithm_index = app.compared[1];
+        app.toggle_compared();
+        assert_eq!(app.compared_names(), ["SHA-256", "MD5", "FNV-1a-32"]);
+        app.toggle_compared();
+        assert_eq!(
+            app.compared_names(),
+            ["SHA-256", "MD5", "FNV-1a-32", "BLAKE3"]
+        );
+
+        app.switch_to_compare();
+        app.original_text = "compare".to_string();
+        app.input_state = Some(InputState::ShowingResult);
+        app.process_compare();
+        assert!(app.comparison_active());
+        assert_eq!(app.comparison.as_ref().unwrap().reports.len(), 4);
+        assert!(app.messages.iter().any(|m| m == "=== Comparison ==="));
+
+        app.compared.truncate(1);
+        app.comparison = None;
+        app.process_compare();
+        assert!(app.comparison.is_none());
+        assert!(
+            app.messages
+                .iter()
+                .any(|m| m.starts_with("Error: select at least two"))
+        );
+    }
+
+    #[test]
+    fn test_format_rate() {
+        assert_eq!(format_rate(12.4), "12/s");
+        assert_eq!(format_rate(45_600.0), "45.6k/s");
+        assert_eq!(format_rate(2_500_000.0), "2.50M/s");
+    }
+}
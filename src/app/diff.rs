@@ -0,0 +1,205 @@
+use crate::app::*;
+
+// Bit-level difference of two equally long digests. Bit positions are in
+// display order, as in `manipulations::digest_bit`.
+#[derive(Clone, Debug, PartialEq)]
+pub struct HashDiff {
+    xor: Vec<u8>,
+    distance: usize,
+}
+
+impl HashDiff {
+    pub fn new(original: &[u8], flipped: &[u8]) -> Result<HashDiff, String> {
+        if original.len() != flipped.len() {
+            return Err(format!(
+                "Digests of different lengths cannot be compared: {} and {} bytes",
+                original.len(),
+                flipped.len()
+            ));
+        }
+        let xor: Vec<u8> = original.iter().zip(flipped).map(|(a, b)| a ^ b).collect();
+        let distance = xor.iter().map(|b| b.count_ones() as usize).sum();
+        Ok(HashDiff { xor, distance })
+    }
+
+    pub fn output_bits(&self) -> usize {
+        self.xor.len() * 8
+    }
+
+    pub fn hamming_distance(&self) -> usize {
+        self.distance
+    }
+
+    pub fn percent(&self) -> f64 {
+        percent(self.distance, self.output_bits())
+    }
+
+    pub fn is_changed(&self, bit: usize) -> bool {
+        manipulations::digest_bit(&self.xor, bit)
+    }
+
+    pub fn changed_bits(&self) -> impl Iterator<Item = usize> + '_ {
+        (0..self.output_bits()).filter(|&bit| self.is_changed(bit))
+    }
+
+    pub fn longest_unchanged_run(&self) -> usize {
+        let mut longest = 0;
+        let mut run = 0;
+        for bit in 0..self.output_bits() {
+            if self.is_changed(bit) {
+                run = 0;
+            } else {
+                run += 1;
+                longest = longest.max(run);
+            }
+        }
+        longest
+    }
+
+    pub fn xor(&self) -> &[u8] {
+        &self.xor
+    }
+
+    pub fn summary_line(&self) -> String {
+        format!(
+            "Changed bits: {} of {}, longest unchanged run: {}",
+            self.distance,
+            self.output_bits(),
+            self.longest_unchanged_run()
+        )
+    }
+}
+
+// Popcount of the XOR without building a `HashDiff`, for the flip loops. Both
+// digests come from the same algorithm, so a mismatch is a bug.
+pub fn hamming_distance(original: &[u8], flipped: &[u8]) -> usize {
+    assert_eq!(
+        original.len(),
+        flipped.len(),
+        "digests of different lengths cannot be compared"
+    );
+    original
+        .iter()
+        .zip(flipped)
+        .map(|(a, b)| (a ^ b).count_ones() as usize)
+        .sum()
+}
+
+pub fn percent(distance: usize, output_bits: usize) -> f64 {
+    if output_bits == 0 {
+        return 0.0;
+    }
+    distance as f64 / output_bits as f64 * 100.0
+}
+
+// Both digests as binary strings, one `ColoredText` per bit: changed bits are
+// red in the original and yellow in the flipped digest, unchanged bits green.
+pub fn render(
+    original: &[u8],
+    flipped: &[u8],
+    diff: &HashDiff,
+) -> (Vec<ColoredText>, Vec<ColoredText>) {
+    let bits = |digest: &[u8], changed_color: Color| -> Vec<ColoredText> {
+        (0..diff.output_bits())
+            .map(|bit| ColoredText {
+                text: if manipulations::digest_bit(digest, bit) {
+                    "1"
+                } else {
+                    "0"
+                }
+                .to_string(),
+                color: if diff.is_changed(bit) {
+                    changed_color
+                } else {
+                    Color::Green
+                },
+            })
+            .collect()
+    };
+    (bits(original, Color::Red), bits(flipped, Color::Yellow))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_identical_digests() {
+        let diff = HashDiff::new(&[0xab, 0xcd], &[0xab, 0xcd]).unwrap();
+        assert_eq!(diff.hamming_distance(), 0);
+        assert_eq!(diff.percent(), 0.0);
+        assert_eq!(diff.changed_bits().count(), 0);
+        assert_eq!(diff.longest_unchanged_run(), 16);
+    }
+
+    #[test]
+    fn test_distance_positions_and_runs() {
+        let diff = HashDiff::new(&[0x00, 0x00, 0x00], &[0x81, 0x00, 0x10]).unwrap();
+        assert_eq!(diff.output_bits(), 24);
+        assert_eq!(diff.hamming_distance(), 3);
+        assert_eq!(diff.percent(), 12.5);
+        assert_eq!(diff.changed_bits().collect::<Vec<_>>(), vec![0, 7, 19]);
+        assert_eq!(diff.longest_unchanged_run(), 11);
+        assert_eq!(diff.xor(), &[0x81, 0x00, 0x10]);
+        assert_eq!(
+            diff.summary_line(),
+            "Changed bits: 3 of 24, longest unchanged run: 11"
+        );
+
+        let all = HashDiff::new(&[0x0f], &[0xf0]).unwrap();
+        assert_eq!(all.percent(), 100.0);
+        assert_eq!(all.longest_unchanged_run(), 0);
+    }
+
+    #[test]
+    fn test_hamming_distance_matches_hash_diff() {
+        let (a, b) = ([0x12, 0x34, 0xff], [0x21, 0x34, 0x00]);
+        assert_eq!(
+            hamming_distance(&a, &b),
+            HashDiff::new(&a, &b).unwrap().hamming_distance()
+        );
+        assert_eq!(percent(6, 24), 25.0);
+        assert_eq!(percent(0, 0), 0.0);
+    }
+
+    #[test]
+    fn test_empty_digests() {
+        let diff = HashDiff::new(&[], &[]).unwrap();
+        assert_eq!(diff.percent(), 0.0);
+        assert_eq!(diff.longest_unchanged_run(), 0);
+    }
+
+    #[test]
+    fn test_length_mismatch_is_an_error() {
+        assert_eq!(
+            HashDiff::new(&[0; 4], &[0; 8]),
+            Err("Digests of different lengths cannot be compared: 4 and 8 bytes".to_string())
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "different lengths")]
+    fn test_hamming_distance_panics_on_length_mismatch() {
+        hamming_distance(&[0; 4], &[0; 8]);
+    }
+
+    #[test]
+    fn test_render_colors_changed_bits() {
+        let (original, flipped) = ([0b1010_0000], [0b0010_0001]);
+        let diff = HashDiff::new(&original, &flipped).unwrap();
+        let (old, new) = render(&original, &flipped, &diff);
+        assert_eq!(old.len(), 8);
+        assert_eq!(
+            old.iter().map(|c| c.text.as_str()).collect::<String>(),
+            "10100000"
+        );
+        assert_eq!(
+            new.iter().map(|c| c.text.as_str()).collect::<String>(),
+            "00100001"
+        );
+        assert_eq!(old[0].color, Color::Red);
+        assert_eq!(new[0].color, Color::Yellow);
+        assert_eq!(new[7].color, Color::Yellow);
+        assert!(old[1..7].iter().all(|c| c.color == Color::Green));
+    }
+}
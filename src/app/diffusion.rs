@@ -0,0 +1,231 @@
+use crate::app::*;
+
+pub const VARIABLE_NAMES: [char; 8] = ['a', 'b', 'c', 'd', 'e', 'f', 'g', 'h'];
+
+pub struct DiffusionView {
+    // Bits flipped in the message.
+    pub bits: Vec<usize>,
+    // Index of the first padded block whose input differs, out of `blocks`.
+    pub block: usize,
+    pub blocks: usize,
+    // Working variables per round for that block, `sha256::MAX_ROUNDS + 1` entries.
+    pub original: Vec<[u32; 8]>,
+    pub flipped: Vec<[u32; 8]>,
+    pub round: usize,
+    pub playing: bool,
+}
+
+impl DiffusionView {
+    pub fn new(original: &[u8], flipped: &[u8], bits: Vec<usize>) -> DiffusionView {
+        let original_blocks = sha256::pad(original);
+        let flipped_blocks = sha256::pad(flipped);
+        let block = original_blocks
+            .chunks_exact(64)
+            .zip(flipped_blocks.chunks_exact(64))
+            .position(|(a, b)| a != b)
+            .unwrap_or(0);
+
+        let mut original_trace = sha256::message_trace(original);
+        let mut flipped_trace = sha256::message_trace(flipped);
+        DiffusionView {
+            bits,
+            block,
+            blocks: original_trace.len(),
+            original: original_trace.swap_remove(block),
+            flipped: flipped_trace.swap_remove(block),
+            round: 0,
+            playing: false,
+        }
+    }
+
+    pub fn differing_bits(&self, round: usize) -> u32 {
+        self.original[round]
+            .iter()
+            .zip(&self.flipped[round])
+            .map(|(a, b)| (a ^ b).count_ones())
+            .sum()
+    }
+
+    pub fn last_round(&self) -> usize {
+        self.original.len() - 1
+    }
+
+    pub fn step(&mut self, delta: isize) {
+        self.round = self
+            .round
+            .saturating_add_signed(delta)
+            .min(self.last_round());
+    }
+
+    pub fn advance(&mut self) {
+        if self.round < self.last_round() {
+            self.round += 1;
+        }
+        if self.round == self.last_round() {
+            self.playing = false;
+        }
+    }
+
+    pub fn toggle_playing(&mut self) {
+        if self.round == self.last_round() {
+            self.round = 0;
+        }
+        self.playing = !self.playing;
+    }
+}
+
+impl App {
+    pub fn switch_to_diffusion(&mut self) {
+        self.current_screen = CurrentScreen::Sandbox;
+        self.current_mode = Some(SandboxMode::Diffusion);
+        self.input_state = Some(InputState::EnteringText);
+        self.messages.clear();
+        self.colored_messages.clear();
+        self.diffusion = None;
+        self.messages.push("Enter string to hash:".to_string());
+    }
+
+    pub fn process_diffusion(&mut self) {
+        let bits = match self.selected_flip_bits() {
+            Ok(bits) => bits,
+            Err(err) => {
+                self.messages.push(format!("Error: {}", err));
+                return;
+            }
+        };
+
+        let mut flipped = self.message().to_vec();
+        flips::apply_flips(&mut flipped, &bits);
+        let view = DiffusionView::new(self.message(), &flipped, bits);
+
+        self.messages.push(format!(
+            "SHA-256 block {} of {}, {} differing bits after {} rounds",
+            view.block + 1,
+            view.blocks,
+            view.differing_bits(view.last_round()),
+            view.last_round()
+        ));
+        self.messages.push("Press Enter to continue...".to_string());
+        self.diffusion = Some(view);
+    }
+
+    pub fn diffusion_active(&self) -> bool {
+        matches!(self.current_mode, Some(SandboxMode::Diffusion))
+            && matches!(self.input_state, Some(InputState::ShowingResult))
+            && self.diffusion.is_some()
+    }
+
+    pub fn toggle_diffusion_playback(&mut self) {
+        if let Some(view) = &mut self.diffusion {
+            view.toggle_playing();
+        }
+    }
+
+    pub fn step_diffusion(&mut self, delta: isize) {
+        if let Some(view) = &mut self.diffusion {
+            view.playing = false;
+            view.step(delta);
+        }
+    }
+
+    pub fn on_tick(&mut self) {
+        if let Some(view) = &mut self.diffusion
+            && view.playing
+        {
+            view.advance();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_view_starts_identical_and_diffuses() {
+        let view = DiffusionView::new(b"abc", b"abd", vec![2]);
+        assert_eq!(view.block, 0);
+        assert_eq!(view.blocks, 1);
+        assert_eq!(view.last_round(), 64);
+        assert_eq!(view.differing_bits(0), 0);
+        assert!(view.differing_bits(1) > 0);
+        assert!(view.differing_bits(64) > 64);
+    }
+
+    #[test]
+    fn test_view_picks_first_differing_block() {
+        let original = vec![0u8; 100];
+        let mut flipped = original.clone();
+        flipped[80] ^= 1;
+
+        let view = DiffusionView::new(&original, &flipped, vec![0]);
+        assert_eq!(view.block, 1);
+        assert_eq!(view.blocks, 2);
+        assert_eq!(view.differing_bits(0), 0);
+    }
+
+    #[test]
+    fn test_step_and_playback() {
+        let mut view = DiffusionView::new(b"abc", b"abd", vec![2]);
+        view.step(-1);
+        assert_eq!(view.round, 0);
+        view.step(5);
+        assert_eq!(view.round, 5);
+        view.step(100);
+        assert_eq!(view.round, 64);
+
+        view.toggle_playing();
+        assert!(view.playing);
+        assert_eq!(view.round, 0);
+        for _ in 0..64 {
+            view.advance();
+        }
+        assert_eq!(view.round, 64);
+        assert!(!view.playing);
+    }
+
+    #[test]
+    fn test_process_diffusion_and_tick() {
+        let mut app = App::new();
+        app.switch_to_diffusion();
+        app.original_text = "hello".to_string();
+        app.bit_index = Some(3);
+        app.input_state = Some(InputState::ShowingResult);
+
+        app.process_diffusion();
+        assert!(app.diffusion_active());
+        assert!(
+            app.messages
+                .iter()
+                .any(|m| m.starts_with("SHA-256 block 1 of 1"))
+        );
+
+        app.on_tick();
+        assert_eq!(app.diffusion.as_ref().unwrap().round, 0);
+        app.toggle_diffusion_playback();
+        app.on_tick();
+        app.on_tick();
+        assert_eq!(app.diffusion.as_ref().unwrap().round, 2);
+
+        app.step_diffusion(-1);
+        let view = app.diffusion.as_ref().unwrap();
+        assert_eq!(view.round, 1);
+        assert!(!view.playing);
+    }
+
+    #[test]
+    fn test_process_diffusion_invalid_spec() {
+        let mut app = App::new();
+        app.switch_to_diffusion();
+        app.original_text = "a".to_string();
+        app.flip_spec = Some(flips::FlipSpec::parse("byte:1").unwrap());
+
+        app.process_diffusion();
+        assert!(app.diffusion.is_none());
+        assert!(
+            app.messages
+                .iter()
+                .any(|m| m.starts_with("Error: Bit index out of range"))
+        );
+    }
+}
@@ -0,0 +1,372 @@
+use crate::app::*;
+use automatic::AutomaticReport;
+use manual::FlipReport;
+use serde::Serialize;
+use statistics::Summary;
+use std::{
+    fs::File,
+    io::{self, BufWriter, Write},
+    path::{Path, PathBuf},
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum ExportFormat {
+    Json,
+    JsonLines,
+    Csv,
+}
+
+impl ExportFormat {
+    pub const ALL: [ExportFormat; 3] = [
+        ExportFormat::Json,
+        ExportFormat::JsonLines,
+        ExportFormat::Csv,
+    ];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            ExportFormat::Json => "JSON",
+            ExportFormat::JsonLines => "JSON Lines",
+            ExportFormat::Csv => "CSV",
+        }
+    }
+
+    pub fn extension(&self) -> &'static str {
+        match self {
+            ExportFormat::Json => "json",
+            ExportFormat::JsonLines => "jsonl",
+            ExportFormat::Csv => "csv",
+        }
+    }
+}
+
+#[derive(Serialize)]
+pub struct FlipRecord {
+    // Set when exactly one bit was flipped.
+    pub bit_index: Option<usize>,
+    pub flipped_bits: Vec<usize>,
+    pub hamming_distance: usize,
+    pub percent: f64,
+    pub original_digest: String,
+    pub flipped_digest: String,
+}
+
+#[derive(Serialize)]
+pub struct RunExport {
+    pub algorithm: &'static str,
+    pub output_bits: usize,
+    pub input_len: usize,
+    // Seed of the random bit positions, when not every bit was flipped or
+    // when a flip spec drew random bits.
+    pub seed: Option<u64>,
+    pub summary: Option<Summary>,
+    pub flips: Vec<FlipRecord>,
+}
+
+#[derive(Serialize)]
+struct JsonLine<'a> {
+    algorithm: &'a str,
+    output_bits: usize,
+    #[serde(flatten)]
+    record: &'a FlipRecord,
+}
+
+impl RunExport {
+    pub fn from_automatic(report: &AutomaticReport) -> RunExport {
+        let original_digest = manipulations::bytes_to_hex(&report.original_digest);
+        RunExport {
+            algorithm: report.algorithm.name(),
+            output_bits: report.algorithm.output_bits(),
+            input_len: report.input_len,
+            seed: report.sample_seed,
+            summary: report.summary(),
+            flips: report
+                .flips
+                .iter()
+                .map(|flip| FlipRecord {
+                    bit_index: Some(flip.bit_index),
+                    flipped_bits: vec![flip.bit_index],
+                    hamming_distance: flip.hamming_distance,
+                    percent: flip.percent,
+                    original_digest: original_digest.clone(),
+                    flipped_digest: manipulations::bytes_to_hex(&flip.flipped_digest),
+                })
+                .collect(),
+        }
+    }
+
+    pub fn from_flip(report: &FlipReport, input_len: usize) -> RunExport {
+        let output_bits = report.algorithm.output_bits();
+        RunExport {
+            algorithm: report.algorithm.name(),
+            output_bits,
+            input_len,
+            seed: report.seed,
+            summary: Summary::from_values(&[report.diff.percent()]),
+            flips: vec![FlipRecord {
+                bit_index: match report.bits[..] {
+                    [bit] => Some(bit),
+                    _ => None,
+                },
+                flipped_bits: report.bits.clone(),
+                hamming_distance: report.diff.hamming_distance(),
+                percent: report.diff.percent(),
+                original_digest: manipulations::bytes_to_hex(&report.original_digest),
+                flipped_digest: manipulations::bytes_to_hex(&report.flipped_digest),
+            }],
+        }
+    }
+
+    pub fn write(&self, format: ExportFormat, out: &mut dyn Write) -> io::Result<()> {
+        match format {
+            ExportFormat::Json => {
+                serde_json::to_writer_pretty(&mut *out, self)?;
+                writeln!(out)?;
+            }
+            ExportFormat::JsonLines => {
+                for record in &self.flips {
+                    let line = JsonLine {
+                        algorithm: self.algorithm,
+                        output_bits: self.output_bits,
+                        record,
+                    };
+                    serde_json::to_writer(&mut *out, &line)?;
+                    writeln!(out)?;
+                }
+            }
+            ExportFormat::Csv => {
+                writeln!(
+                    out,
+                    "algorithm,bit_index,hamming_distance,percent,original_digest,flipped_digest,flipped_bits,seed"
+                )?;
+                for record in &self.flips {
+                    writeln!(
+                        out,
+                        "{},{},{},{},{},{},{},{}",
+                        self.algorithm,
+                        record.bit_index.map_or(String::new(), |i| i.to_string()),
+                        record.hamming_distance,
+                        record.percent,
+                        record.original_digest,
+                        record.flipped_digest,
+                        record
+                            .flipped_bits
+                            .iter()
+                            .map(|bit| bit.to_string())
+                            .collect::<Vec<_>>()
+                            .join(";"),
+                        self.seed.map_or(String::new(), |seed| seed.to_string())
+                    )?;
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+impl App {
+    pub fn cycle_export_format(&mut self) {
+        let current = ExportFormat::ALL
+            .iter()
+            .position(|&f| f == self.export_format)
+            .unwrap_or(0);
+        self.export_format = ExportFormat::ALL[(current + 1) % ExportFormat::ALL.len()];
+    }
+
+    pub fn export_results(&mut self, dir: &Path) {
+        let Some(export) = &self.last_export else {
+            self.messages.push("Nothing to export yet".to_string());
+            return;
+        };
+
+        let millis = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map_or(0, |d| d.as_millis());
+        let slug: String = export
+            .algorithm
+            .chars()
+            .map(|c| {
+                if c.is_ascii_alphanumeric() {
+                    c.to_ascii_lowercase()
+                } else {
+                    '_'
+                }
+            })
+            .collect();
+        let path: PathBuf = dir.join(format!(
+            "avalanche_effect_{}_{}.{}",
+            slug,
+            millis,
+            self.export_format.extension()
+        ));
+
+        let result = File::create(&path).and_then(|file| {
+            let mut writer = BufWriter::new(file);
+            export.write(self.export_format, &mut writer)?;
+            writer.flush()
+        });
+        let message = match result {
+            Ok(()) => format!(
+                "Exported {} flip record(s) as {} to {}",
+                export.flips.len(),
+                self.export_format.name(),
+                path.display()
+            ),
+            Err(err) => format!("Error: export failed: {}", err),
+        };
+        self.messages.push(message);
+        self.scroll_to_bottom();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sample_export() -> RunExport {
+        let report = automatic::analyze(algorithms::ALGORITHMS[0], b"ab");
+        RunExport::from_automatic(&report)
+    }
+
+    fn render(export: &RunExport, format: ExportFormat) -> String {
+        let mut out = Vec::new();
+        export.write(format, &mut out).unwrap();
+        String::from_utf8(out).unwrap()
+    }
+
+    #[test]
+    fn test_export_json_structure() {
+        let export = sample_export();
+        let value: serde_json::Value =
+            serde_json::from_str(&render(&export, ExportFormat::Json)).unwrap();
+
+        assert_eq!(value["algorithm"], "SHA-256");
+        assert_eq!(value["output_bits"], 256);
+        assert_eq!(value["input_len"], 2);
+        assert_eq!(value["flips"].as_array().unwrap().len(), 16);
+        assert_eq!(value["flips"][3]["bit_index"], 3);
+        assert_eq!(
+            value["flips"][0]["original_digest"].as_str().unwrap().len(),
+            64
+        );
+        assert!(value["summary"]["mean"].is_number());
+    }
+
+    #[test]
+    fn test_export_json_lines_one_record_per_line() {
+        let export = sample_export();
+        let output = render(&export, ExportFormat::JsonLines);
+        let lines: Vec<&str> = output.lines().collect();
+        assert_eq!(lines.len(), 16);
+        for (i, line) in lines.iter().enumerate() {
+            let value: serde_json::Value = serde_json::from_str(line).unwrap();
+            assert_eq!(value["algorithm"], "SHA-256");
+            assert_eq!(value["bit_index"], i);
+        }
+    }
+
+    #[test]
+    fn test_export_csv_header_and_rows() {
+        let export = sample_export();
+        let output = render(&export, ExportFormat::Csv);
+        let lines: Vec<&str> = output.lines().collect();
+        assert_eq!(
+            lines[0],
+            "algorithm,bit_index,hamming_distance,percent,original_digest,flipped_digest,flipped_bits,seed"
+        );
+        assert_eq!(lines.len(), 17);
+        assert!(lines[1].starts_with("SHA-256,0,"));
+        assert_eq!(lines[1].split(',').count(), 8);
+    }
+
+    #[test]
+    fn test_export_from_flip_without_bit() {
+        let report = manual::flip(algorithms::ALGORITHMS[0], b"ab", &[]).unwrap();
+        let export = RunExport::from_flip(&report, 2);
+        assert_eq!(export.flips.len(), 1);
+        assert_eq!(export.flips[0].hamming_distance, 0);
+
+        let csv = render(&export, ExportFormat::Csv);
+        assert!(csv.lines().nth(1).unwrap().starts_with("SHA-256,,0,"));
+    }
+
+    #[test]
+    fn test_export_from_multi_bit_flip() {
+        let report = manual::flip(algorithms::ALGORITHMS[0], b"ab", &[1, 2, 9]).unwrap();
+        let export = RunExport::from_flip(&report, 2);
+        assert_eq!(export.flips[0].bit_index, None);
+
+        let csv = render(&export, ExportFormat::Csv);
+        assert!(csv.lines().nth(1).unwrap().ends_with(",1;2;9,"));
+        let json: serde_json::Value =
+            serde_json::from_str(&render(&export, ExportFormat::Json)).unwrap();
+        assert_eq!(
+            json["flips"][0]["flipped_bits"],
+            serde_json::json!([1, 2, 9])
+        );
+    }
+
+    #[test]
+    fn test_export_from_random_flip_records_seed() {
+        let mut app = App::new();
+        app.original_text = "abcd".to_string();
+        app.seed = 0x5eed;
+        app.flip_spec = Some(flips::FlipSpec::parse("random:3").unwrap());
+        app.process_manual_input();
+        assert!(app.messages.iter().any(|m| m == &flips::seed_line(0x5eed)));
+
+        let export = app.last_export.as_ref().unwrap();
+        assert_eq!(export.seed, Some(0x5eed));
+        assert_eq!(export.flips[0].flipped_bits.len(), 3);
+        let json: serde_json::Value =
+            serde_json::from_str(&render(export, ExportFormat::Json)).unwrap();
+        assert_eq!(json["seed"], 0x5eed);
+        let csv = render(export, ExportFormat::Csv);
+        assert!(csv.lines().nth(1).unwrap().ends_with(",24301"));
+
+        // A fixed spec draws nothing, so there is no seed to record.
+        app.flip_spec = Some(flips::FlipSpec::parse("0-2").unwrap());
+        app.process_manual_input();
+        assert_eq!(app.last_export.as_ref().unwrap().seed, None);
+    }
+
+    #[test]
+    fn test_cycle_export_format() {
+        let mut app = App::new();
+        assert_eq!(app.export_format, ExportFormat::Json);
+        app.cycle_export_format();
+        assert_eq!(app.export_format, ExportFormat::JsonLines);
+        app.cycle_export_format();
+        assert_eq!(app.export_format, ExportFormat::Csv);
+        app.cycle_export_format();
+        assert_eq!(app.export_format, ExportFormat::Json);
+    }
+
+    #[test]
+    fn test_export_results_without_run() {
+        let mut app = App::new();
+        app.export_results(&std::env::temp_dir());
+        assert!(app.messages.iter().any(|m| m == "Nothing to export yet"));
+    }
+
+    #[test]
+    fn test_export_results_writes_file() {
+        let dir = std::env::temp_dir().join("avalanche_effect_export_test");
+        std::fs::create_dir_all(&dir).unwrap();
+
+        let mut app = App::new();
+        app.original_text = "ab".to_string();
+        app.export_format = ExportFormat::Csv;
+        app.process_automatic();
+        app.export_results(&dir);
+
+        let message = app.messages.last().unwrap();
+        assert!(message.starts_with("Exported 16 flip record(s) as CSV to "));
+        let path = message.rsplit(" to ").next().unwrap();
+        let content = std::fs::read_to_string(path).unwrap();
+        assert_eq!(content.lines().count(), 17);
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -0,0 +1,330 @@
+use crate::app::*;
+use std::{collections::BTreeSet, str::FromStr};
+
+#[derive(Clone, PartialEq, Debug)]
+pub enum FlipTerm {
+    // Half-open range of bit indices.
+    Bits { start: usize, end: usize },
+    Byte(usize),
+    Burst { len: usize, start: usize },
+    Random(usize),
+}
+
+// Comma-separated list of terms: `5`, `0-7` (inclusive), `100..120`
+// (exclusive), `100..=119`, `byte:2`, `burst:4@10` and `random:3`.
+// Bit indices follow `flip_bit_in_bytes`, so byte N covers bits 8N..8N+7
+// counted from the end of the message.
+#[derive(Clone, PartialEq, Debug)]
+pub struct FlipSpec {
+    pub terms: Vec<FlipTerm>,
+}
+
+impl FlipSpec {
+    pub fn parse(input: &str) -> Result<FlipSpec, String> {
+        let terms = input
+            .split(',')
+            .map(str::trim)
+            .map(parse_term)
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(FlipSpec { terms })
+    }
+
+    // Whether resolving draws from the seed, which then belongs in the report.
+    pub fn is_random(&self) -> bool {
+        self.terms
+            .iter()
+            .any(|term| matches!(term, FlipTerm::Random(_)))
+    }
+
+    // Sorted, deduplicated bit indices; random terms draw from `seed`.
+    pub fn resolve(&self, total_bits: usize, seed: u64) -> Result<Vec<usize>, String> {
+        let mut rng = random::Rng::new(seed);
+        let mut bits = BTreeSet::new();
+        for term in &self.terms {
+            let (start, end) = match *term {
+                FlipTerm::Bits { start, end } => (start, end),
+                FlipTerm::Byte(byte) => {
+                    let start = byte_start(byte)?;
+                    (start, checked_end(start, 8)?)
+                }
+                FlipTerm::Burst { len, start } => (start, checked_end(start, len)?),
+                FlipTerm::Random(count) => {
+                    if count > total_bits {
+                        return Err(format!(
+                            "Cannot flip {} random bits, input has {} bits",
+                            count, total_bits
+                        ));
+                    }
+                    bits.extend(random::sample_indices(total_bits, count, &mut rng));
+                    continue;
+                }
+            };
+            if end > total_bits {
+                return Err(format!(
+                    "Bit index out of range: {} (input has {} bits)",
+                    end - 1,
+                    total_bits
+                ));
+            }
+            bits.extend(start..end);
+        }
+        Ok(bits.into_iter().collect())
+    }
+}
+
+impl FromStr for FlipSpec {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<FlipSpec, String> {
+        FlipSpec::parse(s)
+    }
+}
+
+fn parse_number(input: &str) -> Result<usize, String> {
+    input
+        .trim()
+        .parse()
+        .map_err(|_| format!("'{}' is not a bit index", input.trim()))
+}
+
+// End of the half-open range of `len` bits from `start`.
+fn checked_end(start: usize, len: usize) -> Result<usize, String> {
+    start
+        .checked_add(len)
+        .ok_or_else(|| "bit index too large".to_string())
+}
+
+fn byte_start(byte: usize) -> Result<usize, String> {
+    byte.checked_mul(8)
+        .ok_or_else(|| "bit index too large".to_string())
+}
+
+fn parse_term(term: &str) -> Result<FlipTerm, String> {
+    if term.is_empty() {
+        return Err("empty flip term".to_string());
+    }
+
+    let flip = if let Some(byte) = term.strip_prefix("byte:") {
+        let byte = parse_number(byte)?;
+        checked_end(byte_start(byte)?, 8)?;
+        FlipTerm::Byte(byte)
+    } else if let Some(burst) = term.strip_prefix("burst:") {
+        let (len, start) = burst
+            .split_once('@')
+            .ok_or_else(|| format!("'{}' should look like burst:LEN@START", term))?;
+        let (len, start) = (parse_number(len)?, parse_number(start)?);
+        checked_end(start, len)?;
+        FlipTerm::Burst { len, start }
+    } else if let Some(count) = term.strip_prefix("random:") {
+        FlipTerm::Random(parse_number(count)?)
+    } else if let Some((start, end)) = term.split_once("..=") {
+        FlipTerm::Bits {
+            start: parse_number(start)?,
+            end: checked_end(parse_number(end)?, 1)?,
+        }
+    } else if let Some((start, end)) = term.split_once("..") {
+        FlipTerm::Bits {
+            start: parse_number(start)?,
+            end: parse_number(end)?,
+        }
+    } else if let Some((start, end)) = term.split_once('-') {
+        FlipTerm::Bits {
+            start: parse_number(start)?,
+            end: checked_end(parse_number(end)?, 1)?,
+        }
+    } else {
+        let bit = parse_number(term)?;
+        FlipTerm::Bits {
+            start: bit,
+            end: checked_end(bit, 1)?,
+        }
+    };
+
+    let empty = match flip {
+        FlipTerm::Bits { start, end } => start >= end,
+        FlipTerm::Burst { len, .. } | FlipTerm::Random(len) => len == 0,
+        FlipTerm::Byte(_) => false,
+    };
+    if empty {
+        return Err(format!("'{}' selects no bits", term));
+    }
+    Ok(flip)
+}
+
+impl App {
+    // Bits chosen at the bit index prompt, either a single index or a spec.
+    pub fn selected_flip_bits(&self) -> Result<Vec<usize>, String> {
+        let total_bits = self.message().len() * 8;
+        match (&self.flip_spec, self.bit_index) {
+            (Some(spec), _) => spec.resolve(total_bits, self.seed),
+            (None, Some(idx)) if idx >= total_bits => Err(format!(
+                "Bit index out of range: {} (input has {} bits)",
+                idx, total_bits
+            )),
+            (None, idx) => Ok(idx.into_iter().collect()),
+        }
+    }
+
+    // Seed behind `selected_flip_bits`, when the spec has random terms.
+    pub fn flip_seed(&self) -> Option<u64> {
+        self.flip_spec
+            .as_ref()
+            .filter(|spec| spec.is_random())
+            .map(|_| self.seed)
+    }
+}
+
+pub fn seed_line(seed: u64) -> String {
+    format!("Random bits drawn with seed {:#x}", seed)
+}
+
+pub fn apply_flips(bytes: &mut [u8], bits: &[usize]) {
+    for &bit in bits {
+        manipulations::flip_bit_in_bytes(bytes, bit);
+    }
+}
+
+// Sorted bit indices back into compact ranges, e.g. "0-7, 12".
+pub fn format_bits(bits: &[usize]) -> String {
+    let mut ranges: Vec<(usize, usize)> = Vec::new();
+    for &bit in bits {
+        match ranges.last_mut() {
+            Some((_, end)) if *end + 1 == bit => *end = bit,
+            _ => ranges.push((bit, bit)),
+        }
+    }
+    ranges
+        .iter()
+        .map(|&(start, end)| {
+            if start == end {
+                start.to_string()
+            } else {
+                format!("{}-{}", start, end)
+            }
+        })
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_lists_and_ranges() {
+        let spec = FlipSpec::parse("0-7,12, 100..120,3..=4").unwrap();
+        assert_eq!(
+            spec.terms,
+            vec![
+                FlipTerm::Bits { start: 0, end: 8 },
+                FlipTerm::Bits { start: 12, end: 13 },
+                FlipTerm::Bits {
+                    start: 100,
+                    end: 120
+                },
+                FlipTerm::Bits { start: 3, end: 5 },
+            ]
+        );
+
+        let bits = spec.resolve(128, 0).unwrap();
+        assert_eq!(bits.len(), 8 + 1 + 20);
+        assert_eq!(&bits[..9], &[0, 1, 2, 3, 4, 5, 6, 7, 12]);
+    }
+
+    #[test]
+    fn test_parse_byte_burst_and_random() {
+        let spec = FlipSpec::parse("byte:1,burst:3@20,random:4").unwrap();
+        assert_eq!(
+            spec.terms,
+            vec![
+                FlipTerm::Byte(1),
+                FlipTerm::Burst { len: 3, start: 20 },
+                FlipTerm::Random(4),
+            ]
+        );
+
+        let bits = FlipSpec::parse("byte:1,burst:3@20").unwrap().resolve(32, 0);
+        assert_eq!(
+            bits.unwrap(),
+            vec![8, 9, 10, 11, 12, 13, 14, 15, 20, 21, 22]
+        );
+
+        assert!(spec.is_random());
+        assert!(!FlipSpec::parse("byte:1,burst:3@20").unwrap().is_random());
+        let random = FlipSpec::parse("random:4").unwrap();
+        assert!(random.is_random());
+        let bits = random.resolve(64, 9).unwrap();
+        assert_eq!(bits.len(), 4);
+        assert_eq!(bits, random.resolve(64, 9).unwrap());
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        assert_eq!(
+            FlipSpec::parse("abc").unwrap_err(),
+            "'abc' is not a bit index"
+        );
+        assert_eq!(FlipSpec::parse("1,,2").unwrap_err(), "empty flip term");
+        assert_eq!(
+            FlipSpec::parse("5..5").unwrap_err(),
+            "'5..5' selects no bits"
+        );
+        assert_eq!(
+            FlipSpec::parse("random:0").unwrap_err(),
+            "'random:0' selects no bits"
+        );
+        assert_eq!(
+            FlipSpec::parse("burst:4").unwrap_err(),
+            "'burst:4' should look like burst:LEN@START"
+        );
+    }
+
+    #[test]
+    fn test_parse_rejects_overflowing_indices() {
+        for spec in [
+            "byte:3000000000000000000",
+            "0-18446744073709551615",
+            "0..=18446744073709551615",
+            "18446744073709551615",
+            "burst:2@18446744073709551615",
+        ] {
+            assert_eq!(
+                FlipSpec::parse(spec).unwrap_err(),
+                "bit index too large",
+                "{}",
+                spec
+            );
+        }
+    }
+
+    #[test]
+    fn test_resolve_out_of_range() {
+        assert_eq!(
+            FlipSpec::parse("byte:3")
+                .unwrap()
+                .resolve(24, 0)
+                .unwrap_err(),
+            "Bit index out of range: 31 (input has 24 bits)"
+        );
+        assert!(
+            FlipSpec::parse("random:25")
+                .unwrap()
+                .resolve(24, 0)
+                .is_err()
+        );
+    }
+
+    #[test]
+    fn test_apply_flips_matches_single_flips() {
+        let mut bytes = vec![0u8; 3];
+        apply_flips(&mut bytes, &[0, 9, 23]);
+        assert_eq!(bytes, vec![0x80, 0x02, 0x01]);
+    }
+
+    #[test]
+    fn test_format_bits() {
+        assert_eq!(format_bits(&[]), "");
+        assert_eq!(format_bits(&[5]), "5");
+        assert_eq!(format_bits(&[0, 1, 2, 3, 7, 9, 10]), "0-3, 7, 9-10");
+    }
+}
@@ -0,0 +1,131 @@
+use crate::app::*;
+use automatic::AutomaticReport;
+use statistics::Binomial;
+
+// The expected curve is drawn this many standard deviations either side of
+// its mean; further out it rounds to nothing.
+const TAIL_SIGMAS: f64 = 4.0;
+
+// Hamming distances of one automatic run, for drawing against the binomial
+// distribution an ideal hash would give.
+pub struct DistanceHistogram {
+    pub output_bits: usize,
+    // Flips per Hamming distance, indexed by distance.
+    pub counts: Vec<u64>,
+    pub flips: usize,
+}
+
+pub struct Bin {
+    // Distances `start..end`.
+    pub start: usize,
+    pub end: usize,
+    pub observed: u64,
+    pub expected: f64,
+}
+
+impl DistanceHistogram {
+    pub fn from_report(report: &AutomaticReport) -> Option<DistanceHistogram> {
+        if report.flips.is_empty() {
+            return None;
+        }
+        let output_bits = report.algorithm.output_bits();
+        let mut counts = vec![0; output_bits + 1];
+        for flip in &report.flips {
+            counts[flip.hamming_distance] += 1;
+        }
+        Some(DistanceHistogram {
+            output_bits,
+            counts,
+            flips: report.flips.len(),
+        })
+    }
+
+    pub fn ideal(&self) -> Binomial {
+        Binomial::ideal(self.output_bits)
+    }
+
+    pub fn expected(&self, distance: usize) -> f64 {
+        self.flips as f64 * self.ideal().pmf(distance)
+    }
+
+    // Distances worth drawing: the bulk of the binomial plus every observed
+    // one, so outliers stay on the chart.
+    pub fn window(&self) -> (usize, usize) {
+        let ideal = self.ideal();
+        let spread = TAIL_SIGMAS * ideal.std_dev();
+        let mut start = (ideal.mean() - spread).floor().max(0.0) as usize;
+        let mut end = ((ideal.mean() + spread).ceil() as usize + 1).min(self.output_bits + 1);
+        if let Some(lowest) = self.counts.iter().position(|&n| n > 0) {
+            start = start.min(lowest);
+        }
+        if let Some(highest) = self.counts.iter().rposition(|&n| n > 0) {
+            end = end.max(highest + 1);
+        }
+        (start, end)
+    }
+
+    // Groups the window into at most `columns` bins of equal width.
+    pub fn bins(&self, columns: usize) -> Vec<Bin> {
+        let (start, end) = self.window();
+        let width = (end - start).div_ceil(columns.max(1));
+        (start..end)
+            .step_by(width)
+            .map(|bin_start| {
+                let bin_end = (bin_start + width).min(end);
+                Bin {
+                    start: bin_start,
+                    end: bin_end,
+                    observed: self.counts[bin_start..bin_end].iter().sum(),
+                    expected: (bin_start..bin_end).map(|d| self.expected(d)).sum(),
+                }
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn histogram_of(text: &str) -> DistanceHistogram {
+        let report = automatic::analyze(algorithms::find("SHA-256").unwrap(), text.as_bytes());
+        DistanceHistogram::from_report(&report).unwrap()
+    }
+
+    #[test]
+    fn test_counts_every_flip() {
+        let histogram = histogram_of("avalanche");
+        assert_eq!(histogram.output_bits, 256);
+        assert_eq!(histogram.counts.len(), 257);
+        assert_eq!(histogram.counts.iter().sum::<u64>(), 72);
+        assert_eq!(histogram.flips, 72);
+
+        let empty = automatic::analyze(algorithms::find("SHA-256").unwrap(), b"");
+        assert!(DistanceHistogram::from_report(&empty).is_none());
+    }
+
+    #[test]
+    fn test_window_covers_binomial_and_outliers() {
+        let mut histogram = histogram_of("avalanche");
+        assert_eq!(histogram.window(), (96, 161));
+
+        histogram.counts[3] += 1;
+        histogram.counts[250] += 1;
+        assert_eq!(histogram.window(), (3, 251));
+    }
+
+    #[test]
+    fn test_bins_keep_totals() {
+        let histogram = histogram_of("avalanche");
+        let (start, end) = histogram.window();
+        for columns in [1, 7, 30, 65, 500] {
+            let bins = histogram.bins(columns);
+            assert!(bins.len() <= columns);
+            assert_eq!(bins[0].start, start);
+            assert_eq!(bins.last().unwrap().end, end);
+            assert_eq!(bins.iter().map(|bin| bin.observed).sum::<u64>(), 72);
+            let expected: f64 = bins.iter().map(|bin| bin.expected).sum();
+            assert!((expected - 72.0).abs() < 0.1, "{}", expected);
+        }
+    }
+}
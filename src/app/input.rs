@@ -0,0 +1,396 @@
+use crate::app::*;
+use std::fs;
+
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum Encoding {
+    Utf8,
+    Hex,
+    Base64,
+    Escaped,
+}
+
+impl Encoding {
+    pub fn name(&self) -> &'static str {
+        match self {
+            Encoding::Utf8 => "UTF-8",
+            Encoding::Hex => "Hex",
+            Encoding::Base64 => "Base64",
+            Encoding::Escaped => "\\x escaped",
+        }
+    }
+
+    pub fn decode(&self, input: &str) -> Result<Vec<u8>, String> {
+        match self {
+            Encoding::Utf8 => Ok(input.as_bytes().to_vec()),
+            Encoding::Hex => decode_hex(input),
+            Encoding::Base64 => decode_base64(input),
+            Encoding::Escaped => decode_escaped(input),
+        }
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum InputSource {
+    Text(Encoding),
+    File,
+}
+
+impl InputSource {
+    pub const ALL: [InputSource; 5] = [
+        InputSource::Text(Encoding::Utf8),
+        InputSource::Text(Encoding::Hex),
+        InputSource::Text(Encoding::Base64),
+        InputSource::Text(Encoding::Escaped),
+        InputSource::File,
+    ];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            InputSource::Text(encoding) => encoding.name(),
+            InputSource::File => "File",
+        }
+    }
+}
+
+impl App {
+    pub fn toggle_input_source(&mut self) {
+        if let Some(InputState::EnteringText) = self.input_state {
+            let current = InputSource::ALL
+                .iter()
+                .position(|&s| s == self.input_source)
+                .unwrap_or(0);
+            self.input_source = InputSource::ALL[(current + 1) % InputSource::ALL.len()];
+        }
+    }
+
+    // Decoded or loaded bytes take precedence over the typed text.
+    pub fn message(&self) -> &[u8] {
+        self.input_bytes
+            .as_deref()
+            .unwrap_or(self.original_text.as_bytes())
+    }
+
+    // Moves the input buffer into the message. Returns false and leaves the
+    // buffer untouched when there is nothing to take or it can't be decoded.
+    pub(crate) fn take_message(&mut self) -> bool {
+        if self.input_buffer.is_empty() {
+            return false;
+        }
+
+        let bytes = match self.input_source {
+            InputSource::Text(Encoding::Utf8) => Ok(None),
+            InputSource::Text(encoding) => encoding
+                .decode(&self.input_buffer)
+                .map(Some)
+                .map_err(|err| format!("Error: invalid {} input: {}", encoding.name(), err)),
+            InputSource::File => fs::read(&self.input_buffer)
+                .map(Some)
+                .map_err(|err| format!("Error: cannot read {}: {}", self.input_buffer, err)),
+        };
+        // Rounds and Diffusion always run SHA-256 and Modes AES-128, whatever
+        // is selected; Compare checks each of its hash functions itself.
+        let bytes = bytes.and_then(|bytes| {
+            if matches!(
+                self.current_mode,
+                Some(
+                    SandboxMode::Rounds
+                        | SandboxMode::Diffusion
+                        | SandboxMode::Modes
+                        | SandboxMode::Compare
+                )
+            ) {
+                return Ok(bytes);
+            }
+            let data = bytes.as_deref().unwrap_or(self.input_buffer.as_bytes());
+            algorithms::check_input(self.algorithm(), data)
+                .map(|_| bytes)
+                .map_err(|err| format!("Error: {}", err))
+        });
+        match bytes {
+            Ok(bytes) => self.input_bytes = bytes,
+            Err(message) => {
+                self.messages.push(message);
+                self.scroll_to_bottom();
+                return false;
+            }
+        }
+        self.original_text = std::mem::take(&mut self.input_buffer);
+        self.input_cursor_position = 0;
+        self.input_scroll_offset = 0;
+        true
+    }
+}
+
+pub fn decode_hex(input: &str) -> Result<Vec<u8>, String> {
+    let digits: Vec<(usize, char)> = input
+        .char_indices()
+        .filter(|(_, c)| !c.is_whitespace())
+        .collect();
+    let digits = match digits.as_slice() {
+        [(_, '0'), (_, 'x' | 'X'), rest @ ..] => rest,
+        all => all,
+    };
+    if digits.len() % 2 != 0 {
+        return Err("odd number of hex digits".to_string());
+    }
+
+    digits
+        .chunks(2)
+        .map(|pair| {
+            let mut byte = 0u8;
+            for &(pos, c) in pair {
+                let value = c
+                    .to_digit(16)
+                    .ok_or_else(|| format!("'{}' at position {} is not a hex digit", c, pos))?;
+                byte = (byte << 4) | value as u8;
+            }
+            Ok(byte)
+        })
+        .collect()
+}
+
+pub fn decode_base64(input: &str) -> Result<Vec<u8>, String> {
+    let mut output = Vec::with_capacity(input.len() * 3 / 4);
+    let mut buffer = 0u32;
+    let mut bits = 0;
+    let mut padding = 0;
+    let mut symbols = 0;
+
+    for (pos, c) in input.char_indices().filter(|(_, c)| !c.is_whitespace()) {
+        if c == '=' {
+            padding += 1;
+            symbols += 1;
+            continue;
+        }
+        if padding > 0 {
+            return Err(format!("'{}' at position {} after padding", c, pos));
+        }
+        let value = match c {
+            'A'..='Z' => c as u32 - 'A' as u32,
+            'a'..='z' => c as u32 - 'a' as u32 + 26,
+            '0'..='9' => c as u32 - '0' as u32 + 52,
+            '+' | '-' => 62,
+            '/' | '_' => 63,
+            _ => {
+                return Err(format!(
+                    "'{}' at position {} is not a Base64 character",
+                    c, pos
+                ));
+            }
+        };
+        symbols += 1;
+        buffer = (buffer << 6) | value;
+        bits += 6;
+        if bits >= 8 {
+            bits -= 8;
+            output.push((buffer >> bits) as u8);
+        }
+    }
+
+    if padding > 2 || (padding > 0 && symbols % 4 != 0) {
+        return Err("invalid padding".to_string());
+    }
+    if (symbols - padding) % 4 == 1 {
+        return Err("truncated input".to_string());
+    }
+    Ok(output)
+}
+
+pub fn decode_escaped(input: &str) -> Result<Vec<u8>, String> {
+    let mut output = Vec::with_capacity(input.len());
+    let mut chars = input.char_indices();
+
+    while let Some((pos, c)) = chars.next() {
+        if c != '\\' {
+            let mut utf8 = [0u8; 4];
+            output.extend_from_slice(c.encode_utf8(&mut utf8).as_bytes());
+            continue;
+        }
+        match chars.next() {
+            Some((_, 'x')) => {
+                let hex: String = chars.by_ref().take(2).map(|(_, c)| c).collect();
+                if hex.len() != 2 {
+                    return Err(format!("incomplete \\x escape at position {}", pos));
+                }
+                let byte = u8::from_str_radix(&hex, 16)
+                    .map_err(|_| format!("invalid \\x escape '\\x{}' at position {}", hex, pos))?;
+                output.push(byte);
+            }
+            Some((_, 'n')) => output.push(b'\n'),
+            Some((_, 'r')) => output.push(b'\r'),
+            Some((_, 't')) => output.push(b'\t'),
+            Some((_, '0')) => output.push(0),
+            Some((_, '\\')) => output.push(b'\\'),
+            Some((_, other)) => {
+                return Err(format!("unknown escape '\\{}' at position {}", other, pos));
+            }
+            None => return Err(format!("trailing backslash at position {}", pos)),
+        }
+    }
+    Ok(output)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_toggle_input_source_only_while_entering_text() {
+        let mut app = App::new();
+        app.toggle_input_source();
+        assert_eq!(app.input_source, InputSource::Text(Encoding::Utf8));
+
+        app.switch_to_manual();
+        app.toggle_input_source();
+        assert_eq!(app.input_source, InputSource::Text(Encoding::Hex));
+        assert_eq!(app.get_input_prompt(), "Enter Hex bytes: ");
+        for _ in 0..3 {
+            app.toggle_input_source();
+        }
+        assert_eq!(app.input_source, InputSource::File);
+        assert_eq!(app.get_input_prompt(), "Enter file path: ");
+        app.toggle_input_source();
+        assert_eq!(app.input_source, InputSource::Text(Encoding::Utf8));
+    }
+
+    #[test]
+    fn test_take_message_reads_binary_file() {
+        let path = std::env::temp_dir().join("avalanche_effect_input_test.bin");
+        fs::write(&path, [0x00, 0xff, 0xc3, 0x28]).unwrap();
+
+        let mut app = App::new();
+        app.input_source = InputSource::File;
+        app.input_buffer = path.display().to_string();
+
+        assert!(app.take_message());
+        assert_eq!(app.message(), &[0x00, 0xff, 0xc3, 0x28]);
+        assert!(app.input_buffer.is_empty());
+
+        fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn test_take_message_missing_file_keeps_buffer() {
+        let mut app = App::new();
+        app.input_source = InputSource::File;
+        app.input_buffer = "/nonexistent/avalanche_effect".to_string();
+
+        assert!(!app.take_message());
+        assert_eq!(app.input_buffer, "/nonexistent/avalanche_effect");
+        assert!(
+            app.messages
+                .iter()
+                .any(|m| m.starts_with("Error: cannot read /nonexistent/avalanche_effect"))
+        );
+    }
+
+    #[test]
+    fn test_take_message_text_clears_loaded_file() {
+        let mut app = App::new();
+        app.input_bytes = Some(vec![1, 2, 3]);
+        app.input_buffer = "abc".to_string();
+
+        assert!(app.take_message());
+        assert_eq!(app.message(), b"abc");
+    }
+
+    #[test]
+    fn test_take_message_decodes_hex() {
+        let mut app = App::new();
+        app.input_source = InputSource::Text(Encoding::Hex);
+        app.input_buffer = "00 00 ff".to_string();
+
+        assert!(app.take_message());
+        assert_eq!(app.message(), &[0x00, 0x00, 0xff]);
+    }
+
+    #[test]
+    fn test_take_message_invalid_encoding_reports_error() {
+        let mut app = App::new();
+        app.input_source = InputSource::Text(Encoding::Base64);
+        app.input_buffer = "ab$c".to_string();
+
+        assert!(!app.take_message());
+        assert_eq!(app.input_buffer, "ab$c");
+        assert!(app.messages.iter().any(|m| {
+            m == "Error: invalid Base64 input: '$' at position 2 is not a Base64 character"
+        }));
+    }
+
+    #[test]
+    fn test_take_message_rejects_input_longer_than_cipher_block() {
+        let mut app = App::new();
+        app.switch_to_manual();
+        app.algorithm_index = algorithms::ALGORITHMS
+            .iter()
+            .position(|a| a.name() == "AES-128")
+            .unwrap();
+        app.input_buffer = "seventeen bytes!!".to_string();
+
+        assert!(!app.take_message());
+        assert!(
+            app.messages
+                .iter()
+                .any(|m| m == "Error: AES-128 takes at most 16 bytes of input, got 17")
+        );
+
+        app.input_buffer.pop();
+        assert!(app.take_message());
+    }
+
+    #[test]
+    fn test_decode_hex() {
+        assert_eq!(
+            decode_hex("deadBEEF").unwrap(),
+            vec![0xde, 0xad, 0xbe, 0xef]
+        );
+        assert_eq!(decode_hex("0x01 02\n03").unwrap(), vec![1, 2, 3]);
+        assert_eq!(decode_hex("").unwrap(), Vec::<u8>::new());
+        assert_eq!(decode_hex("abc").unwrap_err(), "odd number of hex digits");
+        assert_eq!(
+            decode_hex("0g").unwrap_err(),
+            "'g' at position 1 is not a hex digit"
+        );
+    }
+
+    #[test]
+    fn test_decode_base64() {
+        assert_eq!(decode_base64("").unwrap(), b"");
+        assert_eq!(decode_base64("Zg==").unwrap(), b"f");
+        assert_eq!(decode_base64("Zm8=").unwrap(), b"fo");
+        assert_eq!(decode_base64("Zm9v").unwrap(), b"foo");
+        assert_eq!(decode_base64("Zm9vYmFy").unwrap(), b"foobar");
+        assert_eq!(decode_base64("Zm9vYg").unwrap(), b"foob");
+        assert_eq!(decode_base64("AAAA AAAA").unwrap(), vec![0; 6]);
+        assert_eq!(decode_base64("_-8=").unwrap(), vec![0xff, 0xef]);
+        assert_eq!(decode_base64("Zm9vY").unwrap_err(), "truncated input");
+        assert_eq!(decode_base64("Zg=").unwrap_err(), "invalid padding");
+        assert!(decode_base64("Zg==Zg==").is_err());
+    }
+
+    #[test]
+    fn test_decode_escaped() {
+        assert_eq!(
+            decode_escaped("a\\x00\\xFFb").unwrap(),
+            vec![b'a', 0x00, 0xff, b'b']
+        );
+        assert_eq!(decode_escaped("\\n\\t\\\\\\0").unwrap(), b"\n\t\\\0");
+        assert_eq!(decode_escaped("é").unwrap(), "é".as_bytes());
+        assert_eq!(
+            decode_escaped("ab\\x4").unwrap_err(),
+            "incomplete \\x escape at position 2"
+        );
+        assert_eq!(
+            decode_escaped("\\xzz").unwrap_err(),
+            "invalid \\x escape '\\xzz' at position 0"
+        );
+        assert_eq!(
+            decode_escaped("\\q").unwrap_err(),
+            "unknown escape '\\q' at position 0"
+        );
+        assert_eq!(
+            decode_escaped("a\\").unwrap_err(),
+            "trailing backslash at position 1"
+        );
+    }
+}
@@ -0,0 +1,191 @@
+use crate::app::*;
+use rayon::prelude::*;
+use worker::Progress;
+
+pub const LINEARITY_MESSAGES: usize = 8;
+pub const LINEARITY_MAX_BITS: usize = 64;
+
+pub struct LinearityReport {
+    pub messages: usize,
+    pub seed: u64,
+    // Input bits checked, every bit for short inputs.
+    pub tested_bits: Vec<usize>,
+    // Tested bits whose output difference was the same for every message.
+    pub constant_bits: Vec<usize>,
+    // Output difference of the first constant bit.
+    pub example: Option<(usize, Vec<u8>)>,
+}
+
+impl LinearityReport {
+    pub fn is_linear(&self) -> bool {
+        !self.constant_bits.is_empty()
+    }
+}
+
+// The input message followed by random messages of the same length, so
+// memory stays flat even for large files.
+fn message(data: &[u8], index: usize, seed: u64) -> Vec<u8> {
+    if index == 0 {
+        return data.to_vec();
+    }
+    random::MessageKind::Uniform.message(data.len(), seed, index)
+}
+
+fn xor(a: &[u8], b: &[u8]) -> Vec<u8> {
+    a.iter().zip(b).map(|(x, y)| x ^ y).collect()
+}
+
+// Flags input bits for which f(x ⊕ Δ) ⊕ f(x) is the same for every message x,
+// which holds for all bits of an affine function such as a CRC.
+pub fn detect(
+    algorithm: &'static dyn HashAlgorithm,
+    data: &[u8],
+    messages: usize,
+    seed: u64,
+) -> LinearityReport {
+    detect_with_progress(algorithm, data, messages, seed, &Progress::none())
+}
+
+pub fn detect_with_progress(
+    algorithm: &'static dyn HashAlgorithm,
+    data: &[u8],
+    messages: usize,
+    seed: u64,
+    progress: &Progress,
+) -> LinearityReport {
+    let total_bits = data.len() * 8;
+    let tested_bits = if total_bits > LINEARITY_MAX_BITS {
+        let mut rng = random::Rng::new(seed);
+        random::sample_indices(total_bits, LINEARITY_MAX_BITS, &mut rng)
+    } else {
+        (0..total_bits).collect()
+    };
+    progress.stage("Linearity", tested_bits.len());
+    let digests: Vec<Vec<u8>> = (0..messages)
+        .take_while(|_| !progress.is_cancelled())
+        .map(|index| algorithm.hash(&message(data, index, seed)))
+        .collect();
+
+    let differences: Vec<Option<Vec<u8>>> = tested_bits
+        .par_iter()
+        .map(|&bit| {
+            if !progress.step() {
+                return None;
+            }
+            let mut first: Option<Vec<u8>> = None;
+            for (index, digest) in digests.iter().enumerate() {
+                let mut flipped = message(data, index, seed);
+                manipulations::flip_bit_in_bytes(&mut flipped, bit);
+                let difference = xor(digest, &algorithm.hash(&flipped));
+                match &first {
+                    None => first = Some(difference),
+                    Some(expected) if *expected != difference => return None,
+                    Some(_) => {}
+                }
+            }
+            first
+        })
+        .collect();
+
+    let constant: Vec<(usize, Vec<u8>)> = tested_bits
+        .iter()
+        .zip(differences)
+        .filter_map(|(&bit, difference)| difference.map(|d| (bit, d)))
+        .collect();
+
+    LinearityReport {
+        messages,
+        seed,
+        constant_bits: constant.iter().map(|(bit, _)| *bit).collect(),
+        example: constant.into_iter().next(),
+        tested_bits,
+    }
+}
+
+pub fn linearity_lines(report: &LinearityReport) -> Vec<String> {
+    if report.tested_bits.is_empty() || report.messages < 2 {
+        return Vec::new();
+    }
+    let mut lines = vec![
+        "=== Linearity: is f(x ⊕ Δ) ⊕ f(x) the same for every x? ===".to_string(),
+        format!(
+            "Message-independent output difference: {} of {} input bits across {} messages -> {}",
+            report.constant_bits.len(),
+            report.tested_bits.len(),
+            report.messages,
+            if report.is_linear() { "FAIL" } else { "PASS" }
+        ),
+    ];
+    if let Some((bit, difference)) = &report.example {
+        lines.push(format!(
+            "Flipping bit {} always XORs the output with {}",
+            bit,
+            manipulations::bytes_to_hex(difference)
+        ));
+    }
+    lines.push(format!(
+        "Messages: the input and {} random ones (seed {:#x})",
+        report.messages - 1,
+        report.seed
+    ));
+    lines
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn detect_named(name: &str, data: &[u8]) -> LinearityReport {
+        detect(
+            algorithms::find(name).unwrap(),
+            data,
+            LINEARITY_MESSAGES,
+            random::DEFAULT_SEED,
+        )
+    }
+
+    #[test]
+    fn test_crc_is_linear_for_every_bit() {
+        let report = detect_named("CRC-32", b"abcd");
+        assert_eq!(report.tested_bits.len(), 32);
+        assert_eq!(report.constant_bits, report.tested_bits);
+        assert!(report.is_linear());
+
+        let (bit, difference) = report.example.clone().unwrap();
+        assert_eq!(bit, 0);
+        let lines = linearity_lines(&report);
+        assert!(lines[1].ends_with("32 of 32 input bits across 8 messages -> FAIL"));
+        assert_eq!(
+            lines[2],
+            format!(
+                "Flipping bit 0 always XORs the output with {}",
+                manipulations::bytes_to_hex(&difference)
+            )
+        );
+        assert_eq!(
+            lines[3],
+            format!(
+                "Messages: the input and 7 random ones (seed {:#x})",
+                random::DEFAULT_SEED
+            )
+        );
+    }
+
+    #[test]
+    fn test_sha256_and_adler_are_not_linear() {
+        assert!(!detect_named("SHA-256", b"abcd").is_linear());
+        assert!(detect_named("Adler-32", b"abcd").constant_bits.len() < 32);
+    }
+
+    #[test]
+    fn test_large_input_samples_bits() {
+        let report = detect_named("CRC-16/ARC", &[0u8; 100]);
+        assert_eq!(report.tested_bits.len(), LINEARITY_MAX_BITS);
+        assert!(report.is_linear());
+    }
+
+    #[test]
+    fn test_empty_input_has_no_lines() {
+        assert!(linearity_lines(&detect_named("CRC-8", b"")).is_empty());
+    }
+}
@@ -1,6 +1,10 @@
 pub fn str_to_bits(input: &str) -> Vec<u8> {
+    bytes_to_bits(input.as_bytes())
+}
+
+pub fn bytes_to_bits(input: &[u8]) -> Vec<u8> {
     let mut result = Vec::with_capacity(input.len() * 8);
-    for byte in input.as_bytes().iter() {
+    for byte in input.iter() {
         for i in (0..8).rev() {
             result.push((byte >> i) & 1);
         }
@@ -28,14 +32,46 @@ pub fn reverse_bit(input: &[u8], bit_idx: usize) -> Vec<u8> {
     res
 }
 
+// Same indexing as `reverse_bit`: bit 0 is the least significant bit of the last byte.
+pub fn flip_bit_in_bytes(bytes: &mut [u8], bit_idx: usize) {
+    if bit_idx >= bytes.len() * 8 {
+        return;
+    }
+    let byte = bytes.len() - 1 - bit_idx / 8;
+    bytes[byte] ^= 1 << (bit_idx % 8);
+}
+
+// Digest bits are read in display order, i.e. the order of `bytes_to_binary_string`.
+pub fn digest_bit(bytes: &[u8], bit_idx: usize) -> bool {
+    bytes[bit_idx / 8] & (0x80 >> (bit_idx % 8)) != 0
+}
+
+pub fn bytes_to_binary_string(bytes: &[u8]) -> String {
+    bytes.iter().map(|b| format!("{:08b}", b)).collect()
+}
+
+pub fn bytes_to_hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|b| format!("{:02x}", b)).collect()
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    #[test]
+    fn bytes_to_bits_non_utf8() {
+        let input = [0xff, 0x00, 0x81];
+        let bits = bytes_to_bits(&input);
+        assert_eq!(bits.len(), 24);
+        assert_eq!(&bits[..8], &[1; 8]);
+        assert_eq!(&bits[16..], &[1, 0, 0, 0, 0, 0, 0, 1]);
+        assert_eq!(bits_to_bytes(&bits), input);
+    }
+
     #[test]
     fn str_to_bits_empty() {
         let input = "";
-        let valid_output = vec![];
+        let valid_output: Vec<u8> = vec![];
         assert_eq!(str_to_bits(input), valid_output);
     }
 
@@ -82,7 +118,7 @@ mod tests {
     #[test]
     fn bits_to_bytes_empty() {
         let input = vec![];
-        let valid_output = vec![];
+        let valid_output: Vec<u8> = vec![];
         assert_eq!(bits_to_bytes(&input), valid_output);
     }
 
@@ -147,7 +183,7 @@ mod tests {
     fn test_reverse_bit_empty() {
         let input_vec = vec![];
         let idx = 0;
-        let valid_output = vec![];
+        let valid_output: Vec<u8> = vec![];
         assert_eq!(reverse_bit(&input_vec, idx), valid_output);
 
         let idx = 1;
@@ -173,4 +209,43 @@ mod tests {
         valid_output.extend(vec![0, 1, 0]);
         assert_eq!(reverse_bit(&input_vec, idx), valid_output);
     }
+
+    #[test]
+    fn test_bytes_to_binary_string() {
+        assert_eq!(bytes_to_binary_string(&[]), "");
+        assert_eq!(bytes_to_binary_string(&[1, 255]), "0000000111111111");
+    }
+
+    #[test]
+    fn test_flip_bit_in_bytes_matches_reverse_bit() {
+        let input = "qwerty";
+        let bits = str_to_bits(input);
+        for idx in 0..bits.len() {
+            let mut bytes = input.as_bytes().to_vec();
+            flip_bit_in_bytes(&mut bytes, idx);
+            assert_eq!(bytes, bits_to_bytes(&reverse_bit(&bits, idx)));
+        }
+    }
+
+    #[test]
+    fn test_flip_bit_in_bytes_out_of_range() {
+        let mut bytes = vec![1, 2];
+        flip_bit_in_bytes(&mut bytes, 16);
+        assert_eq!(bytes, vec![1, 2]);
+    }
+
+    #[test]
+    fn test_digest_bit_display_order() {
+        let bytes = [0b1000_0000, 0b0000_0001];
+        let binary = bytes_to_binary_string(&bytes);
+        for (idx, ch) in binary.chars().enumerate() {
+            assert_eq!(digest_bit(&bytes, idx), ch == '1');
+        }
+    }
+
+    #[test]
+    fn test_bytes_to_hex() {
+        assert_eq!(bytes_to_hex(&[]), "");
+        assert_eq!(bytes_to_hex(&[0, 15, 255]), "000fff");
+    }
 }
@@ -1,5 +1,6 @@
 use crate::app::*;
-use sha2::{Digest, Sha256};
+use diff::HashDiff;
+use export::RunExport;
 
 impl App {
     pub fn switch_to_manual(&mut self) {
@@ -12,45 +13,94 @@ impl App {
     }
 
     pub fn process_manual_input(&mut self) {
-        let original_bits = manipulations::str_to_bits(&self.original_text);
-
-        let (modified_bits, _) = if let Some(idx) = self.bit_index {
-            if idx < original_bits.len() {
-                (manipulations::reverse_bit(&original_bits, idx), Some(idx))
-            } else {
-                self.messages
-                    .push("Error: Bit index out of range".to_string());
+        let report = match self
+            .selected_flip_bits()
+            .and_then(|bits| flip(self.algorithm(), self.message(), &bits))
+        {
+            Ok(report) => FlipReport {
+                seed: self.flip_seed(),
+                ..report
+            },
+            Err(err) => {
+                self.messages.push(format!("Error: {}", err));
                 return;
             }
-        } else {
-            (original_bits.clone(), None)
         };
 
-        let mut hasher = Sha256::new();
-        hasher.update(self.original_text.as_bytes());
-        let hash1 = hasher.finalize();
-        let hash1: String = hash1.iter().map(|b| format!("{:08b}", b)).collect();
-
-        let mut hasher = Sha256::new();
-        hasher.update(modified_bits);
-        let hash2 = hasher.finalize();
-        let hash2: String = hash2.iter().map(|b| format!("{:08b}", b)).collect();
-
-        let (percent, old_colored, new_colored) = statistics::percent_difference(&hash1, &hash2);
-
         self.messages.push("".to_string());
-        self.messages.push("Hash comparison:".to_string());
+        self.messages
+            .push(format!("Hash comparison ({}):", report.algorithm.name()));
+        if report.bits.len() > 1 {
+            self.messages.push(format!(
+                "Flipped bits: {} ({} bits)",
+                flips::format_bits(&report.bits),
+                report.bits.len()
+            ));
+        }
+        if let Some(seed) = report.seed {
+            self.messages.push(flips::seed_line(seed));
+        }
+        let (original_colored, flipped_colored) = report.render();
         self.messages.push("Original hash:".to_string());
-        self.add_colored_text_message(&old_colored);
+        self.add_colored_text_message(&original_colored);
         self.messages.push("New hash:".to_string());
-        self.add_colored_text_message(&new_colored);
+        self.add_colored_text_message(&flipped_colored);
         self.messages
-            .push(format!("Avalanche effect: {:.2}%", percent));
+            .push(format!("Avalanche effect: {:.2}%", report.diff.percent()));
+        self.messages.push(report.diff.summary_line());
         self.messages.push("".to_string());
         self.messages.push("Press Enter to continue...".to_string());
+        self.last_export = Some(RunExport::from_flip(&report, self.message().len()));
     }
 }
 
+pub struct FlipReport {
+    pub algorithm: &'static dyn HashAlgorithm,
+    // Sorted input bits that were flipped, empty for no flip.
+    pub bits: Vec<usize>,
+    pub original_digest: Vec<u8>,
+    pub flipped_digest: Vec<u8>,
+    pub diff: HashDiff,
+    // Seed of the `random:` flip terms the bits came from, if any.
+    pub seed: Option<u64>,
+}
+
+impl FlipReport {
+    pub fn render(&self) -> (Vec<ColoredText>, Vec<ColoredText>) {
+        diff::render(&self.original_digest, &self.flipped_digest, &self.diff)
+    }
+}
+
+pub fn flip(
+    algorithm: &'static dyn HashAlgorithm,
+    data: &[u8],
+    bits: &[usize],
+) -> Result<FlipReport, String> {
+    algorithms::check_input(algorithm, data)?;
+    if let Some(&idx) = bits.iter().find(|&&idx| idx >= data.len() * 8) {
+        return Err(format!(
+            "Bit index out of range: {} (input has {} bits)",
+            idx,
+            data.len() * 8
+        ));
+    }
+    let mut modified = data.to_vec();
+    flips::apply_flips(&mut modified, bits);
+
+    let original_digest = algorithm.hash(data);
+    let flipped_digest = algorithm.hash(&modified);
+    let diff = HashDiff::new(&original_digest, &flipped_digest)?;
+
+    Ok(FlipReport {
+        algorithm,
+        bits: bits.to_vec(),
+        original_digest,
+        flipped_digest,
+        diff,
+        seed: None,
+    })
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -140,6 +190,54 @@ mod tests {
         assert!(avalanche_message.contains("0.") || avalanche_message.contains("%"));
     }
 
+    #[test]
+    fn test_process_manual_input_no_flip_is_zero_percent() {
+        let mut app = App::new();
+        app.original_text = "test".to_string();
+        app.bit_index = None;
+
+        app.process_manual_input();
+
+        assert!(app.messages.iter().any(|m| m == "Avalanche effect: 0.00%"));
+        assert!(
+            app.messages
+                .iter()
+                .any(|m| m == "Changed bits: 0 of 256, longest unchanged run: 256")
+        );
+    }
+
+    #[test]
+    fn test_process_manual_input_uses_selected_algorithm() {
+        let mut app = App::new();
+        app.original_text = "test".to_string();
+        app.bit_index = Some(3);
+        app.algorithm_index = algorithms::ALGORITHMS
+            .iter()
+            .position(|a| a.name() == "MD5")
+            .unwrap();
+
+        app.process_manual_input();
+
+        assert!(
+            app.messages
+                .iter()
+                .any(|m| m.contains("Hash comparison (MD5)"))
+        );
+        assert_eq!(app.colored_messages[0].len(), 128);
+    }
+
+    #[test]
+    fn test_flip_reports_digests() {
+        let report = flip(algorithms::ALGORITHMS[0], b"abc", &[0]).unwrap();
+        assert_eq!(report.bits, vec![0]);
+        assert_eq!(report.original_digest.len(), 32);
+        assert_ne!(report.original_digest, report.flipped_digest);
+        assert_eq!(report.diff.output_bits(), 256);
+        assert_eq!(report.render().0.len(), 256);
+
+        assert!(flip(algorithms::ALGORITHMS[0], b"abc", &[24]).is_err());
+    }
+
     #[test]
     fn test_switch_to_manual_clears_previous_state() {
         let mut app = App::new();
@@ -151,4 +249,64 @@ mod tests {
         assert_eq!(app.messages.len(), 1);
         assert!(app.colored_messages.is_empty());
     }
+
+    #[test]
+    fn test_flip_binary_input() {
+        let data = [0xde, 0xad, 0x00, 0xff];
+        let report = flip(algorithms::ALGORITHMS[0], &data, &[31]).unwrap();
+        assert_eq!(
+            report.flipped_digest,
+            algorithms::ALGORITHMS[0].hash(&[0x5e, 0xad, 0x00, 0xff])
+        );
+    }
+
+    #[test]
+    fn test_process_manual_input_with_flip_spec() {
+        let mut app = App::new();
+        app.original_text = "abcd".to_string();
+        app.flip_spec = Some(flips::FlipSpec::parse("0-7,12").unwrap());
+
+        app.process_manual_input();
+
+        assert!(
+            app.messages
+                .iter()
+                .any(|m| m == "Flipped bits: 0-7, 12 (9 bits)")
+        );
+        assert!(app.messages.iter().any(|m| m.contains("Avalanche effect")));
+    }
+
+    #[test]
+    fn test_process_manual_input_flip_spec_out_of_range() {
+        let mut app = App::new();
+        app.original_text = "a".to_string();
+        app.flip_spec = Some(flips::FlipSpec::parse("burst:4@6").unwrap());
+
+        app.process_manual_input();
+
+        assert!(
+            app.messages
+                .iter()
+                .any(|m| m == "Error: Bit index out of range: 9 (input has 8 bits)")
+        );
+    }
+
+    #[test]
+    fn test_flip_cipher_key() {
+        let aes = algorithms::find("AES-128-key").unwrap();
+        let report = flip(aes, b"key", &[0]).unwrap();
+        assert_eq!(report.original_digest.len(), 16);
+        assert!(report.diff.percent() > 20.0);
+
+        assert!(flip(aes, &[0; 17], &[0]).is_err());
+    }
+
+    #[test]
+    fn test_flip_multiple_bits() {
+        let report = flip(algorithms::ALGORITHMS[0], &[0x00, 0x00], &[0, 15]).unwrap();
+        assert_eq!(
+            report.flipped_digest,
+            algorithms::ALGORITHMS[0].hash(&[0x80, 0x01])
+        );
+    }
 }
@@ -1,9 +1,44 @@
 use ratatui::style::Color;
 
+use algorithms::{ALGORITHMS, HashAlgorithm};
+use compare::Comparison;
+use diffusion::DiffusionView;
+use export::{ExportFormat, RunExport};
+use flips::FlipSpec;
+use histogram::DistanceHistogram;
+use input::{Encoding, InputSource};
+use random::MessageKind;
+use sampling::SamplingPlan;
+use worker::{Job, Progress};
+
+pub mod algorithms;
 pub mod automatic;
+pub mod bias;
+pub mod bic;
+pub mod checksums;
+pub mod ciphers;
+pub mod compare;
+pub mod diff;
+pub mod diffusion;
+pub mod export;
+pub mod flips;
+pub mod histogram;
+pub mod input;
+pub mod linearity;
 pub mod manipulations;
 pub mod manual;
+pub mod modes;
+pub mod noncrypto;
+pub mod random;
+pub mod rounds;
+pub mod sac;
+pub mod sampling;
+pub mod sensitivity;
+pub mod sha256;
 pub mod statistics;
+pub mod worker;
+
+pub const SIGNIFICANCE_LEVELS: [f64; 3] = [0.05, 0.01, 0.001];
 
 pub enum CurrentScreen {
     Menu,
@@ -11,9 +46,16 @@ pub enum CurrentScreen {
     Exiting,
 }
 
+#[derive(Clone, Copy)]
 pub enum SandboxMode {
     Automatic,
     Manual,
+    Sac,
+    Bic,
+    Rounds,
+    Diffusion,
+    Modes,
+    Compare,
 }
 
 pub enum InputState {
@@ -36,7 +78,31 @@ pub struct App {
     pub input_buffer: String,
     pub input_state: Option<InputState>,
     pub original_text: String,
+    pub input_source: InputSource,
+    pub input_bytes: Option<Vec<u8>>,
     pub bit_index: Option<usize>,
+    // Set instead of `bit_index` when the input was more than a single bit.
+    pub flip_spec: Option<FlipSpec>,
+    pub algorithm_index: usize,
+    pub significance_level: f64,
+    pub sampling: SamplingPlan,
+    // Every random choice of an analysis derives from this seed, and the
+    // reports print it, so any run can be repeated.
+    pub seed: u64,
+    pub message_kind: MessageKind,
+    pub export_format: ExportFormat,
+    pub last_export: Option<RunExport>,
+    pub diffusion: Option<DiffusionView>,
+    // Hamming distances of the last automatic run, drawn below its report.
+    pub histogram: Option<DistanceHistogram>,
+    // Indices into `ALGORITHMS` of the hash functions compared side by side.
+    pub compared: Vec<usize>,
+    pub comparison: Option<Comparison>,
+    // Text analyses run on a worker thread, as in the TUI, instead of inside
+    // `submit_input`.
+    pub run_in_background: bool,
+    pub job: Option<Job>,
+    pub(crate) progress: Progress,
 
     pub input_cursor_position: usize,
     pub input_scroll_offset: usize,
@@ -60,13 +126,64 @@ impl App {
             input_buffer: String::new(),
             input_state: None,
             original_text: String::new(),
+            input_source: InputSource::Text(Encoding::Utf8),
+            input_bytes: None,
             bit_index: None,
+            flip_spec: None,
+            algorithm_index: 0,
+            significance_level: SIGNIFICANCE_LEVELS[0],
+            sampling: SamplingPlan::default(),
+            seed: random::DEFAULT_SEED,
+            message_kind: MessageKind::Uniform,
+            export_format: ExportFormat::Json,
+            last_export: None,
+            diffusion: None,
+            histogram: None,
+            compared: compare::default_compared(),
+            comparison: None,
+            run_in_background: false,
+            job: None,
+            progress: Progress::none(),
             input_cursor_position: 0,
             input_scroll_offset: 0,
             output_scroll_offset: 0,
         }
     }
 
+    pub fn algorithm(&self) -> &'static dyn HashAlgorithm {
+        ALGORITHMS[self.algorithm_index]
+    }
+
+    pub fn select_next_algorithm(&mut self) {
+        self.algorithm_index = (self.algorithm_index + 1) % ALGORITHMS.len();
+    }
+
+    pub fn select_previous_algorithm(&mut self) {
+        self.algorithm_index = (self.algorithm_index + ALGORITHMS.len() - 1) % ALGORITHMS.len();
+    }
+
+    pub fn cycle_significance_level(&mut self) {
+        let current = SIGNIFICANCE_LEVELS
+            .iter()
+            .position(|&level| level == self.significance_level)
+            .unwrap_or(SIGNIFICANCE_LEVELS.len() - 1);
+        self.significance_level = SIGNIFICANCE_LEVELS[(current + 1) % SIGNIFICANCE_LEVELS.len()];
+    }
+
+    // Derived from the current seed, so a sequence of new seeds is itself
+    // reproducible.
+    pub fn next_seed(&mut self) {
+        self.seed = random::Rng::new(self.seed).next_u64();
+    }
+
+    pub fn cycle_message_kind(&mut self) {
+        let current = MessageKind::ALL
+            .iter()
+            .position(|&kind| kind == self.message_kind)
+            .unwrap_or(0);
+        self.message_kind = MessageKind::ALL[(current + 1) % MessageKind::ALL.len()];
+    }
+
     pub fn handle_input(&mut self, c: char) {
         if let Some(InputState::EnteringText | InputState::EnteringBitIndex) = &self.input_state {
             self.input_buffer.insert(self.input_cursor_position, c);
@@ -194,97 +311,93 @@ impl App {
     }
 
     pub fn submit_input(&mut self) {
+        if self.job_running() {
+            return;
+        }
         if let Some(state) = &self.input_state
             && let Some(sandbox_mode) = &self.current_mode
         {
             match sandbox_mode {
-                SandboxMode::Manual => match state {
+                SandboxMode::Manual | SandboxMode::Diffusion | SandboxMode::Modes => match state {
                     InputState::EnteringText => {
-                        if !self.input_buffer.is_empty() {
-                            self.original_text = self.input_buffer.clone();
-                            self.input_buffer.clear();
-                            self.input_cursor_position = 0;
-                            self.input_scroll_offset = 0;
+                        if self.take_message() {
                             self.input_state = Some(InputState::EnteringBitIndex);
-                            let first_10: String =
-                                self.original_text.chars().clone().take(10).collect();
-                            let last_10: String = self
-                                .original_text
-                                .chars()
-                                .clone()
-                                .rev()
-                                .take(10)
-                                .collect::<Vec<_>>()
-                                .into_iter()
-                                .rev()
-                                .collect();
-
-                            self.messages.push(format!(
-                                "Text in format first..last: {}...{}",
-                                first_10, last_10
-                            )); // TODO remove harcode, do better format 
+                            self.push_text_preview();
 
                             self.messages.push(
                                 "Enter bit index to flip (or press Enter for no flip):".to_string(),
                             );
+                            self.messages.push(
+                                "Several bits: 0-7,12 / 100..120 / byte:2 / burst:4@10 / random:3"
+                                    .to_string(),
+                            );
                             self.scroll_to_bottom();
                         }
                     }
                     InputState::EnteringBitIndex => {
-                        let bit_idx = if self.input_buffer.is_empty() {
-                            None
+                        let input = self.input_buffer.trim();
+                        if input.is_empty() {
+                            self.bit_index = None;
+                            self.flip_spec = None;
+                        } else if let Ok(idx) = input.parse() {
+                            self.bit_index = Some(idx);
+                            self.flip_spec = None;
                         } else {
-                            self.input_buffer.parse().ok()
-                        };
+                            match FlipSpec::parse(input) {
+                                Ok(spec) => {
+                                    self.bit_index = None;
+                                    self.flip_spec = Some(spec);
+                                }
+                                Err(err) => {
+                                    self.messages.push(format!("Error: {}", err));
+                                    self.scroll_to_bottom();
+                                    return;
+                                }
+                            }
+                        }
 
-                        self.bit_index = bit_idx;
                         self.input_buffer.clear();
                         self.input_cursor_position = 0;
                         self.input_scroll_offset = 0;
                         self.input_state = Some(InputState::ShowingResult);
-                        self.process_manual_input();
+                        match self.current_mode {
+                            Some(SandboxMode::Diffusion) => self.process_diffusion(),
+                            Some(SandboxMode::Modes) => self.process_modes(),
+                            _ => self.process_manual_input(),
+                        }
                         self.scroll_to_bottom();
                     }
                     InputState::ShowingResult => {
                         self.input_state = Some(InputState::EnteringText);
+                        self.diffusion = None;
                         self.messages.clear();
                         self.colored_messages.clear();
                         self.output_scroll_offset = 0;
                         self.messages.push("Enter string to hash:".to_string());
                     }
                 },
-                SandboxMode::Automatic => match state {
+                SandboxMode::Automatic
+                | SandboxMode::Sac
+                | SandboxMode::Bic
+                | SandboxMode::Rounds
+                | SandboxMode::Compare => match state {
                     InputState::EnteringText => {
-                        if !self.input_buffer.is_empty() {
-                            self.original_text = self.input_buffer.clone();
-                            self.input_buffer.clear();
-                            self.input_cursor_position = 0;
-                            self.input_scroll_offset = 0;
-                            let first_10: String =
-                                self.original_text.chars().clone().take(10).collect();
-                            let last_10: String = self
-                                .original_text
-                                .chars()
-                                .clone()
-                                .rev()
-                                .take(10)
-                                .collect::<Vec<_>>()
-                                .into_iter()
-                                .rev()
-                                .collect();
-
-                            self.messages.push(format!(
-                                "Text in format first..last: {}...{}",
-                                first_10, last_10
-                            )); // TODO remove harcode, do better format 
-
-                            self.process_automatic();
+                        if self.take_message() {
+                            self.push_text_preview();
+
+                            if self.run_in_background {
+                                self.start_job();
+                            } else {
+                                self.process_text_analysis();
+                            }
                             self.input_state = Some(InputState::ShowingResult);
                             self.scroll_to_bottom();
                         }
                     }
                     InputState::ShowingResult => {
                         self.input_state = Some(InputState::EnteringText);
+                        self.comparison = None;
+                        self.histogram = None;
                         self.messages.clear();
                         self.colored_messages.clear();
                         self.output_scroll_offset = 0;
@@ -296,6 +409,55 @@ impl App {
         }
     }
 
+    fn process_text_analysis(&mut self) {
+        match self.current_mode {
+            Some(SandboxMode::Sac) => self.process_sac(),
+            Some(SandboxMode::Bic) => self.process_bic(),
+            Some(SandboxMode::Rounds) => self.process_rounds(),
+            Some(SandboxMode::Compare) => self.process_compare(),
+            _ => self.process_automatic(),
+        }
+    }
+
+    fn push_text_preview(&mut self) {
+        if self.input_bytes.is_some() {
+            let message = self.message();
+            let preview = if message.len() <= 16 {
+                manipulations::bytes_to_hex(message)
+            } else {
+                format!(
+                    "{}...{}",
+                    manipulations::bytes_to_hex(&message[..8]),
+                    manipulations::bytes_to_hex(&message[message.len() - 8..])
+                )
+            };
+            let source = match self.input_source {
+                InputSource::File => format!("File {}", self.original_text),
+                other => format!("{} input", other.name()),
+            };
+            self.messages
+                .push(format!("{} ({} bytes): {}", source, message.len(), preview));
+            return;
+        }
+
+        let first_10: String = self.original_text.chars().clone().take(10).collect();
+        let last_10: String = self
+            .original_text
+            .chars()
+            .clone()
+            .rev()
+            .take(10)
+            .collect::<Vec<_>>()
+            .into_iter()
+            .rev()
+            .collect();
+
+        self.messages.push(format!(
+            "Text in format first..last: {}...{}",
+            first_10, last_10
+        )); // TODO remove harcode, do better format
+    }
+
     fn scroll_to_bottom(&mut self) {
         if self.messages.len() > 10 {
             self.output_scroll_offset = self.messages.len().saturating_sub(10);
@@ -305,7 +467,13 @@ impl App {
     pub fn get_input_prompt(&self) -> String {
         if let Some(state) = &self.input_state {
             match state {
-                InputState::EnteringText => "Enter text: ",
+                InputState::EnteringText => match self.input_source {
+                    InputSource::Text(Encoding::Utf8) => "Enter text: ",
+                    InputSource::Text(encoding) => {
+                        return format!("Enter {} bytes: ", encoding.name());
+                    }
+                    InputSource::File => "Enter file path: ",
+                },
                 InputState::EnteringBitIndex => "Enter bit index: ",
                 InputState::ShowingResult => "Press Enter to continue...",
             }
@@ -321,12 +489,17 @@ impl App {
     }
 
     pub fn switch_to_menu(&mut self) {
+        self.cancel_job();
         self.current_screen = CurrentScreen::Menu;
         self.current_mode = None;
         self.input_state = None;
         self.input_buffer.clear();
         self.messages.clear();
         self.colored_messages.clear();
+        self.last_export = None;
+        self.diffusion = None;
+        self.histogram = None;
+        self.comparison = None;
     }
 }
 
@@ -346,6 +519,60 @@ mod tests {
         assert_eq!(app.output_scroll_offset, 0);
     }
 
+    #[test]
+    fn test_default_algorithm_is_sha256() {
+        let app = App::new();
+        assert_eq!(app.algorithm().name(), "SHA-256");
+    }
+
+    #[test]
+    fn test_select_algorithm_wraps_around() {
+        let mut app = App::new();
+        app.select_previous_algorithm();
+        assert_eq!(app.algorithm_index, ALGORITHMS.len() - 1);
+
+        app.select_next_algorithm();
+        assert_eq!(app.algorithm_index, 0);
+
+        app.select_next_algorithm();
+        assert_eq!(app.algorithm_index, 1);
+    }
+
+    #[test]
+    fn test_cycle_significance_level() {
+        let mut app = App::new();
+        assert_eq!(app.significance_level, 0.05);
+
+        app.cycle_significance_level();
+        assert_eq!(app.significance_level, 0.01);
+        app.cycle_significance_level();
+        assert_eq!(app.significance_level, 0.001);
+        app.cycle_significance_level();
+        assert_eq!(app.significance_level, 0.05);
+
+        app.significance_level = 0.2;
+        app.cycle_significance_level();
+        assert_eq!(app.significance_level, 0.05);
+    }
+
+    #[test]
+    fn test_seed_and_message_kind_settings() {
+        let mut app = App::new();
+        assert_eq!(app.seed, random::DEFAULT_SEED);
+        app.next_seed();
+        let first = app.seed;
+        assert_ne!(first, random::DEFAULT_SEED);
+
+        let mut again = App::new();
+        again.next_seed();
+        assert_eq!(again.seed, first);
+
+        for kind in MessageKind::ALL.iter().cycle().skip(1).take(4) {
+            app.cycle_message_kind();
+            assert_eq!(app.message_kind, *kind);
+        }
+    }
+
     #[test]
     fn test_handle_input_entering_text() {
         let mut app = App::new();
@@ -758,6 +985,139 @@ mod tests {
         assert!(app.messages.contains(&"Enter string to hash:".to_string()));
     }
 
+    #[test]
+    fn test_submit_input_sac_entering_text() {
+        let mut app = App::new();
+        app.current_mode = Some(SandboxMode::Sac);
+        app.input_state = Some(InputState::EnteringText);
+        app.input_buffer = "ab".to_string();
+
+        app.submit_input();
+        assert_eq!(app.original_text, "ab");
+        assert!(matches!(app.input_state, Some(InputState::ShowingResult)));
+        assert!(
+            app.messages
+                .iter()
+                .any(|m| m.contains("Strict Avalanche Criterion"))
+        );
+
+        app.submit_input();
+        assert!(matches!(app.input_state, Some(InputState::EnteringText)));
+    }
+
+    #[test]
+    fn test_submit_input_bic_entering_text() {
+        let mut app = App::new();
+        app.current_mode = Some(SandboxMode::Bic);
+        app.input_state = Some(InputState::EnteringText);
+        app.input_buffer = "a".to_string();
+
+        app.submit_input();
+        assert!(matches!(app.input_state, Some(InputState::ShowingResult)));
+        assert!(
+            app.messages
+                .iter()
+                .any(|m| m.contains("Bit Independence Criterion"))
+        );
+    }
+
+    #[test]
+    fn test_submit_input_file_through_manual_and_automatic() {
+        let path = std::env::temp_dir().join("avalanche_effect_submit_test.bin");
+        std::fs::write(&path, [0xfe, 0x00, 0x9a]).unwrap();
+
+        let mut app = App::new();
+        app.current_mode = Some(SandboxMode::Manual);
+        app.input_state = Some(InputState::EnteringText);
+        app.input_source = InputSource::File;
+        app.input_buffer = path.display().to_string();
+
+        app.submit_input();
+        assert!(matches!(
+            app.input_state,
+            Some(InputState::EnteringBitIndex)
+        ));
+        assert!(
+            app.messages
+                .iter()
+                .any(|m| m.ends_with("(3 bytes): fe009a"))
+        );
+
+        app.input_buffer = "23".to_string();
+        app.submit_input();
+        assert!(app.messages.iter().any(|m| m.contains("Avalanche effect")));
+
+        app.current_mode = Some(SandboxMode::Automatic);
+        app.input_state = Some(InputState::EnteringText);
+        app.input_buffer = path.display().to_string();
+        app.submit_input();
+        assert!(app.messages.iter().any(|m| m == "Flips: 24"));
+
+        std::fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn test_submit_input_invalid_hex_stays_on_prompt() {
+        let mut app = App::new();
+        app.current_mode = Some(SandboxMode::Automatic);
+        app.input_state = Some(InputState::EnteringText);
+        app.input_source = InputSource::Text(Encoding::Hex);
+        app.input_buffer = "abc".to_string();
+
+        app.submit_input();
+        assert!(matches!(app.input_state, Some(InputState::EnteringText)));
+        assert!(
+            app.messages
+                .iter()
+                .any(|m| m == "Error: invalid Hex input: odd number of hex digits")
+        );
+
+        app.input_buffer = "00".repeat(64);
+        app.submit_input();
+        assert!(matches!(app.input_state, Some(InputState::ShowingResult)));
+        assert!(
+            app.messages
+                .iter()
+                .any(|m| m == "Hex input (64 bytes): 0000000000000000...0000000000000000")
+        );
+        assert!(app.messages.iter().any(|m| m == "Flips: 512"));
+    }
+
+    #[test]
+    fn test_submit_input_manual_flip_spec() {
+        let mut app = App::new();
+        app.current_mode = Some(SandboxMode::Manual);
+        app.input_state = Some(InputState::EnteringBitIndex);
+        app.original_text = "hello".to_string();
+        app.input_buffer = "0-3".to_string();
+
+        app.submit_input();
+        assert_eq!(app.bit_index, None);
+        assert_eq!(app.flip_spec, Some(FlipSpec::parse("0-3").unwrap()));
+        assert!(matches!(app.input_state, Some(InputState::ShowingResult)));
+    }
+
+    #[test]
+    fn test_submit_input_manual_invalid_flip_spec() {
+        let mut app = App::new();
+        app.current_mode = Some(SandboxMode::Manual);
+        app.input_state = Some(InputState::EnteringBitIndex);
+        app.original_text = "hello".to_string();
+        app.input_buffer = "burst:3".to_string();
+
+        app.submit_input();
+        assert!(matches!(
+            app.input_state,
+            Some(InputState::EnteringBitIndex)
+        ));
+        assert_eq!(app.input_buffer, "burst:3");
+        assert!(
+            app.messages
+                .iter()
+                .any(|m| m == "Error: 'burst:3' should look like burst:LEN@START")
+        );
+    }
+
     #[test]
     fn test_submit_input_empty_text_ignored() {
         let mut app = App::new();
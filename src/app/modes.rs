@@ -0,0 +1,408 @@
+use crate::app::*;
+use aes::cipher::{BlockDecrypt, BlockEncrypt, KeyInit};
+use sac::heatmap_color;
+
+pub const BLOCK_LEN: usize = 16;
+pub const MAX_MODE_BLOCKS: usize = 16;
+
+// NIST SP 800-38A example IV, also the initial CTR counter block.
+pub const FIXED_IV: [u8; BLOCK_LEN] = [
+    0x00, 0x01, 0x02, 0x03, 0x04, 0x05, 0x06, 0x07, 0x08, 0x09, 0x0a, 0x0b, 0x0c, 0x0d, 0x0e, 0x0f,
+];
+
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum Mode {
+    Ecb,
+    Cbc,
+    Cfb,
+    Ofb,
+    Ctr,
+}
+
+impl Mode {
+    pub const ALL: [Mode; 5] = [Mode::Ecb, Mode::Cbc, Mode::Cfb, Mode::Ofb, Mode::Ctr];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Mode::Ecb => "ECB",
+            Mode::Cbc => "CBC",
+            Mode::Cfb => "CFB",
+            Mode::Ofb => "OFB",
+            Mode::Ctr => "CTR",
+        }
+    }
+
+    // AES-128 under the fixed key and IV; the input must be whole blocks.
+    pub fn encrypt(self, plaintext: &[u8]) -> Vec<u8> {
+        let cipher = fixed_cipher();
+        let mut output = Vec::with_capacity(plaintext.len());
+        // Previous ciphertext block for CBC and CFB, keystream block for OFB,
+        // counter block for CTR.
+        let mut feedback = FIXED_IV;
+        for block in plaintext.chunks(BLOCK_LEN) {
+            let encrypted = match self {
+                Mode::Ecb => encrypt_block(&cipher, block),
+                Mode::Cbc => {
+                    feedback = encrypt_block(&cipher, &xor(block, &feedback));
+                    feedback
+                }
+                Mode::Cfb => {
+                    feedback = xor(block, &encrypt_block(&cipher, &feedback));
+                    feedback
+                }
+                Mode::Ofb => {
+                    feedback = encrypt_block(&cipher, &feedback);
+                    xor(block, &feedback)
+                }
+                Mode::Ctr => {
+                    let encrypted = xor(block, &encrypt_block(&cipher, &feedback));
+                    feedback = (u128::from_be_bytes(feedback).wrapping_add(1)).to_be_bytes();
+                    encrypted
+                }
+            };
+            output.extend_from_slice(&encrypted);
+        }
+        output
+    }
+
+    pub fn decrypt(self, ciphertext: &[u8]) -> Vec<u8> {
+        if let Mode::Ofb | Mode::Ctr = self {
+            return self.encrypt(ciphertext);
+        }
+
+        let cipher = fixed_cipher();
+        let mut output = Vec::with_capacity(ciphertext.len());
+        let mut feedback = FIXED_IV;
+        for block in ciphertext.chunks(BLOCK_LEN) {
+            let decrypted = match self {
+                Mode::Cbc => xor(&decrypt_block(&cipher, block), &feedback),
+                Mode::Cfb => xor(block, &encrypt_block(&cipher, &feedback)),
+                _ => decrypt_block(&cipher, block),
+            };
+            feedback.copy_from_slice(block);
+            output.extend_from_slice(&decrypted);
+        }
+        output
+    }
+}
+
+fn fixed_cipher() -> aes::Aes128 {
+    aes::Aes128::new(ciphers::FIXED_KEY[..BLOCK_LEN].into())
+}
+
+fn encrypt_block(cipher: &aes::Aes128, block: &[u8]) -> [u8; BLOCK_LEN] {
+    let mut output = [0u8; BLOCK_LEN];
+    output.copy_from_slice(block);
+    cipher.encrypt_block(aes::Block::from_mut_slice(&mut output));
+    output
+}
+
+fn decrypt_block(cipher: &aes::Aes128, block: &[u8]) -> [u8; BLOCK_LEN] {
+    let mut output = [0u8; BLOCK_LEN];
+    output.copy_from_slice(block);
+    cipher.decrypt_block(aes::Block::from_mut_slice(&mut output));
+    output
+}
+
+fn xor(a: &[u8], b: &[u8; BLOCK_LEN]) -> [u8; BLOCK_LEN] {
+    let mut output = *b;
+    for (out, byte) in output.iter_mut().zip(a) {
+        *out ^= byte;
+    }
+    output
+}
+
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum Direction {
+    // Plaintext bits flipped before encrypting.
+    Encrypt,
+    // The same ciphertext bits flipped before decrypting.
+    Decrypt,
+}
+
+pub struct ModePropagation {
+    pub mode: Mode,
+    // Changed bits per block, in message order.
+    pub encryption: Vec<u32>,
+    pub decryption: Vec<u32>,
+}
+
+impl ModePropagation {
+    pub fn changes(&self, direction: Direction) -> &[u32] {
+        match direction {
+            Direction::Encrypt => &self.encryption,
+            Direction::Decrypt => &self.decryption,
+        }
+    }
+}
+
+pub fn check_message_len(len: usize) -> Result<(), String> {
+    if len > MAX_MODE_BLOCKS * BLOCK_LEN {
+        return Err(format!(
+            "mode analysis supports messages up to {} bytes, got {}",
+            MAX_MODE_BLOCKS * BLOCK_LEN,
+            len
+        ));
+    }
+    Ok(())
+}
+
+// Zero-pads the message to whole blocks and flips `bits`, which are counted
+// from the end of the message as in `flip_bit_in_bytes`.
+fn padded(message: &[u8], bits: &[usize]) -> Vec<u8> {
+    let mut padded = message.to_vec();
+    flips::apply_flips(&mut padded, bits);
+    padded.resize(message.len().div_ceil(BLOCK_LEN) * BLOCK_LEN, 0);
+    padded
+}
+
+fn block_changes(a: &[u8], b: &[u8]) -> Vec<u32> {
+    a.chunks(BLOCK_LEN)
+        .zip(b.chunks(BLOCK_LEN))
+        .map(|(a, b)| a.iter().zip(b).map(|(x, y)| (x ^ y).count_ones()).sum())
+        .collect()
+}
+
+pub fn propagation(mode: Mode, message: &[u8], bits: &[usize]) -> ModePropagation {
+    let plaintext = padded(message, &[]);
+    let ciphertext = mode.encrypt(&plaintext);
+    let encryption = block_changes(&ciphertext, &mode.encrypt(&padded(message, bits)));
+
+    // Flip the ciphertext bytes that line up with the flipped message bytes.
+    let mut corrupted = ciphertext;
+    for &bit in bits {
+        corrupted[message.len() - 1 - bit / 8] ^= 1 << (bit % 8);
+    }
+    let decryption = block_changes(&plaintext, &mode.decrypt(&corrupted));
+
+    ModePropagation {
+        mode,
+        encryption,
+        decryption,
+    }
+}
+
+// 1-based blocks containing the flipped bits.
+pub fn flipped_blocks(message_len: usize, bits: &[usize]) -> Vec<usize> {
+    let mut blocks: Vec<usize> = bits
+        .iter()
+        .map(|&bit| (message_len - 1 - bit / 8) / BLOCK_LEN + 1)
+        .collect();
+    blocks.sort_unstable();
+    blocks.dedup();
+    blocks
+}
+
+// `seed` is set when random flip terms chose the bits.
+pub fn summary_lines(message_len: usize, bits: &[usize], seed: Option<u64>) -> Vec<String> {
+    let blocks = flipped_blocks(message_len, bits)
+        .iter()
+        .map(|block| block.to_string())
+        .collect::<Vec<_>>()
+        .join(", ");
+    let mut lines = vec![
+        "=== Block cipher modes (AES-128, fixed key and IV) ===".to_string(),
+        format!(
+            "Message: {} bytes in {} blocks, zero-padded",
+            message_len,
+            message_len.div_ceil(BLOCK_LEN)
+        ),
+        if bits.is_empty() {
+            "Flipped bits: none".to_string()
+        } else {
+            format!(
+                "Flipped bits: {} (block {})",
+                flips::format_bits(bits),
+                blocks
+            )
+        },
+    ];
+    lines.extend(seed.map(flips::seed_line));
+    lines
+}
+
+pub fn grid_title(direction: Direction) -> &'static str {
+    match direction {
+        Direction::Encrypt => "Plaintext bits flipped, changed bits per ciphertext block:",
+        Direction::Decrypt => "Ciphertext bits flipped, changed bits per decrypted block:",
+    }
+}
+
+// One row per mode and one cell per block: green for untouched blocks,
+// yellow for a few flipped bits, red for a scrambled block.
+pub fn grid_rows(results: &[ModePropagation], direction: Direction) -> Vec<Vec<ColoredText>> {
+    let blocks = results.first().map_or(0, |r| r.encryption.len());
+    let mut header = vec![ColoredText {
+        text: format!("{:>6} | ", "Block"),
+        color: Color::Gray,
+    }];
+    header.extend((1..=blocks).map(|block| ColoredText {
+        text: format!("{:>4}", block),
+        color: Color::Gray,
+    }));
+
+    let mut rows = vec![header];
+    for result in results {
+        let mut row = vec![ColoredText {
+            text: format!("{:>6} | ", result.mode.name()),
+            color: Color::Gray,
+        }];
+        row.extend(result.changes(direction).iter().map(|&bits| ColoredText {
+            text: format!("{:>4}", bits),
+            color: heatmap_color(bits as f64, 0.0, 8.0),
+        }));
+        rows.push(row);
+    }
+    rows
+}
+
+impl App {
+    pub fn switch_to_modes(&mut self) {
+        self.current_screen = CurrentScreen::Sandbox;
+        self.current_mode = Some(SandboxMode::Modes);
+        self.input_state = Some(InputState::EnteringText);
+        self.messages.clear();
+        self.colored_messages.clear();
+        self.messages.push("Enter message to encrypt:".to_string());
+    }
+
+    pub fn process_modes(&mut self) {
+        let message_len = self.message().len();
+        let bits = match check_message_len(message_len).and_then(|_| self.selected_flip_bits()) {
+            Ok(bits) => bits,
+            Err(err) => {
+                self.messages.push(format!("Error: {}", err));
+                return;
+            }
+        };
+
+        let results: Vec<ModePropagation> = Mode::ALL
+            .iter()
+            .map(|&mode| propagation(mode, self.message(), &bits))
+            .collect();
+
+        self.messages.push("".to_string());
+        self.messages
+            .extend(summary_lines(message_len, &bits, self.flip_seed()));
+        for direction in [Direction::Encrypt, Direction::Decrypt] {
+            self.messages.push("".to_string());
+            self.messages.push(grid_title(direction).to_string());
+            for row in grid_rows(&results, direction) {
+                self.add_colored_text_message(&row);
+            }
+        }
+        self.messages.push("".to_string());
+        self.messages.push("Press Enter to continue...".to_string());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Three blocks, with the flip in the middle one.
+    const MESSAGE: &[u8; 48] = b"0123456789abcdef0123456789abcdef0123456789abcdef";
+    const MIDDLE_BIT: usize = 16 * 8 + 3;
+
+    fn changed(bits: &[u32]) -> Vec<bool> {
+        bits.iter().map(|&b| b > 0).collect()
+    }
+
+    #[test]
+    fn test_round_trip_and_known_block() {
+        for mode in Mode::ALL {
+            let ciphertext = mode.encrypt(MESSAGE);
+            assert_ne!(&ciphertext[..], &MESSAGE[..]);
+            assert_eq!(mode.decrypt(&ciphertext), MESSAGE);
+        }
+
+        let plaintext = &ciphers::FIXED_PLAINTEXT[..BLOCK_LEN];
+        assert_eq!(
+            manipulations::bytes_to_hex(&Mode::Ecb.encrypt(plaintext)),
+            "69c4e0d86a7b0430d8cdb78070b4c55a"
+        );
+    }
+
+    #[test]
+    fn test_encryption_propagation_per_mode() {
+        let expect = [
+            (Mode::Ecb, [false, true, false]),
+            (Mode::Cbc, [false, true, true]),
+            (Mode::Cfb, [false, true, true]),
+            (Mode::Ofb, [false, true, false]),
+            (Mode::Ctr, [false, true, false]),
+        ];
+        for (mode, pattern) in expect {
+            let result = propagation(mode, MESSAGE, &[MIDDLE_BIT]);
+            assert_eq!(changed(&result.encryption), pattern, "{}", mode.name());
+        }
+
+        let ctr = propagation(Mode::Ctr, MESSAGE, &[MIDDLE_BIT]);
+        assert_eq!(ctr.encryption[1], 1);
+    }
+
+    #[test]
+    fn test_decryption_propagation_per_mode() {
+        let cbc = propagation(Mode::Cbc, MESSAGE, &[MIDDLE_BIT]);
+        assert_eq!(cbc.decryption[0], 0);
+        assert!(cbc.decryption[1] > 8);
+        assert_eq!(cbc.decryption[2], 1);
+
+        let cfb = propagation(Mode::Cfb, MESSAGE, &[MIDDLE_BIT]);
+        assert_eq!(cfb.decryption[1], 1);
+        assert!(cfb.decryption[2] > 8);
+
+        let ecb = propagation(Mode::Ecb, MESSAGE, &[MIDDLE_BIT]);
+        assert_eq!(changed(&ecb.decryption), [false, true, false]);
+
+        for mode in [Mode::Ofb, Mode::Ctr] {
+            assert_eq!(
+                propagation(mode, MESSAGE, &[MIDDLE_BIT]).decryption,
+                [0, 1, 0]
+            );
+        }
+    }
+
+    #[test]
+    fn test_partial_block_is_padded() {
+        let result = propagation(Mode::Cbc, b"abc", &[0]);
+        assert_eq!(result.encryption.len(), 1);
+        assert_eq!(flipped_blocks(20, &[0, 159]), vec![1, 2]);
+        assert!(check_message_len(256).is_ok());
+        assert!(check_message_len(257).is_err());
+    }
+
+    #[test]
+    fn test_process_modes_draws_grids() {
+        let mut app = App::new();
+        app.switch_to_modes();
+        app.input_bytes = Some(MESSAGE.to_vec());
+        app.bit_index = Some(MIDDLE_BIT);
+
+        app.process_modes();
+
+        assert!(
+            app.messages
+                .iter()
+                .any(|m| m == "Flipped bits: 131 (block 2)")
+        );
+        assert_eq!(app.colored_messages.len(), 2 * (Mode::ALL.len() + 1));
+        assert_eq!(app.colored_messages[1][0].text, "   ECB | ");
+        assert_eq!(app.colored_messages[1][1].color, Color::Green);
+        assert_eq!(app.colored_messages[1][2].color, Color::Red);
+    }
+
+    #[test]
+    fn test_process_modes_rejects_long_message() {
+        let mut app = App::new();
+        app.input_bytes = Some(vec![0; 300]);
+
+        app.process_modes();
+
+        assert!(
+            app.messages
+                .iter()
+                .any(|m| m == "Error: mode analysis supports messages up to 256 bytes, got 300")
+        );
+    }
+}
@@ -0,0 +1,146 @@
+use crate::app::algorithms::HashAlgorithm;
+use std::hash::Hasher;
+
+// Hash functions built for hash tables and checksums rather than security.
+// The integer result is shown big-endian, as these hashes are usually printed.
+pub struct IntegerHash {
+    name: &'static str,
+    output_bits: usize,
+    function: fn(&[u8]) -> u128,
+}
+
+impl IntegerHash {
+    pub const fn new(name: &'static str, output_bits: usize, function: fn(&[u8]) -> u128) -> Self {
+        IntegerHash {
+            name,
+            output_bits,
+            function,
+        }
+    }
+}
+
+impl HashAlgorithm for IntegerHash {
+    fn name(&self) -> &'static str {
+        self.name
+    }
+
+    fn output_bits(&self) -> usize {
+        self.output_bits
+    }
+
+    fn hash(&self, data: &[u8]) -> Vec<u8> {
+        let bytes = (self.function)(data).to_be_bytes();
+        bytes[bytes.len() - self.output_bits / 8..].to_vec()
+    }
+}
+
+pub fn fnv1a_32(data: &[u8]) -> u128 {
+    let mut hash: u32 = 0x811c9dc5;
+    for &byte in data {
+        hash ^= byte as u32;
+        hash = hash.wrapping_mul(0x01000193);
+    }
+    hash as u128
+}
+
+pub fn fnv1a_64(data: &[u8]) -> u128 {
+    let mut hash: u64 = 0xcbf29ce484222325;
+    for &byte in data {
+        hash ^= byte as u64;
+        hash = hash.wrapping_mul(0x00000100000001b3);
+    }
+    hash as u128
+}
+
+pub fn murmur3_32(data: &[u8]) -> u128 {
+    murmur3::murmur3_32(&mut &data[..], 0).expect("reading a slice cannot fail") as u128
+}
+
+pub fn murmur3_128(data: &[u8]) -> u128 {
+    murmur3::murmur3_x64_128(&mut &data[..], 0).expect("reading a slice cannot fail")
+}
+
+pub fn xxhash32(data: &[u8]) -> u128 {
+    twox_hash::XxHash32::oneshot(0, data) as u128
+}
+
+pub fn xxhash64(data: &[u8]) -> u128 {
+    twox_hash::XxHash64::oneshot(0, data) as u128
+}
+
+pub fn xxh3_64(data: &[u8]) -> u128 {
+    twox_hash::XxHash3_64::oneshot(data) as u128
+}
+
+pub fn xxh3_128(data: &[u8]) -> u128 {
+    twox_hash::XxHash3_128::oneshot(data)
+}
+
+pub fn cityhash_128(data: &[u8]) -> u128 {
+    cityhash_rs::cityhash_110_128(data)
+}
+
+pub fn siphash_1_3(data: &[u8]) -> u128 {
+    finish(siphasher::sip::SipHasher13::new(), data)
+}
+
+pub fn siphash_2_4(data: &[u8]) -> u128 {
+    finish(siphasher::sip::SipHasher24::new(), data)
+}
+
+// Uses fixed keys, unlike the randomly keyed `RandomState` of std's HashMap;
+// the algorithm itself is unspecified and may change between Rust releases.
+pub fn default_hasher(data: &[u8]) -> u128 {
+    finish(std::hash::DefaultHasher::new(), data)
+}
+
+fn finish(mut hasher: impl Hasher, data: &[u8]) -> u128 {
+    hasher.write(data);
+    hasher.finish() as u128
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::app::{algorithms, manipulations::bytes_to_hex};
+
+    fn hex(name: &str, data: &[u8]) -> String {
+        bytes_to_hex(&algorithms::find(name).unwrap().hash(data))
+    }
+
+    #[test]
+    fn test_known_vectors() {
+        assert_eq!(hex("FNV-1a-32", b"a"), "e40c292c");
+        assert_eq!(hex("FNV-1a-64", b"a"), "af63dc4c8601ec8c");
+        assert_eq!(hex("Murmur3-32", b"hello"), "248bfa47");
+        assert_eq!(hex("xxHash32", b""), "02cc5d05");
+        assert_eq!(hex("xxHash64", b""), "ef46db3751d8e999");
+        assert_eq!(hex("XXH3-64", b""), "2d06800538d394c2");
+        assert_eq!(hex("SipHash-2-4", b""), "1e924b9d737700d7");
+    }
+
+    #[test]
+    fn test_output_is_truncated_to_width() {
+        assert_eq!(algorithms::find("FNV-1a-32").unwrap().hash(b"x").len(), 4);
+        assert_eq!(
+            algorithms::find("CityHash128").unwrap().hash(b"x").len(),
+            16
+        );
+        assert_eq!(
+            algorithms::find("DefaultHasher").unwrap().hash(b"x"),
+            algorithms::find("DefaultHasher").unwrap().hash(b"x")
+        );
+    }
+
+    #[test]
+    fn test_fnv1a_last_byte_never_reaches_low_bits() {
+        // The final multiply only carries upwards, so flipping bit k of the
+        // last byte leaves the k lowest output bits untouched.
+        let original = fnv1a_32(b"avalanche");
+        let mut flipped = b"avalanche".to_vec();
+        *flipped.last_mut().unwrap() ^= 0x80;
+        let diff = original ^ fnv1a_32(&flipped);
+        assert_ne!(diff, 0);
+        assert_eq!(diff & 0x7f, 0);
+    }
+}
@@ -0,0 +1,256 @@
+pub const DEFAULT_SEED: u64 = 0x5eed_a1a7_c4e0_0001;
+
+fn splitmix64(state: &mut u64) -> u64 {
+    *state = state.wrapping_add(0x9e37_79b9_7f4a_7c15);
+    let mut z = *state;
+    z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+    z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+    z ^ (z >> 31)
+}
+
+// xoshiro256** seeded through SplitMix64, small enough to keep in-crate
+// and stable across platforms and dependency upgrades.
+#[derive(Clone)]
+pub struct Rng {
+    state: [u64; 4],
+}
+
+impl Rng {
+    pub fn new(seed: u64) -> Rng {
+        let mut sm = seed;
+        Rng {
+            state: [
+                splitmix64(&mut sm),
+                splitmix64(&mut sm),
+                splitmix64(&mut sm),
+                splitmix64(&mut sm),
+            ],
+        }
+    }
+
+    pub fn for_stream(seed: u64, stream: u64) -> Rng {
+        let mut sm = seed ^ stream.wrapping_mul(0xd134_2543_de82_ef95);
+        Rng::new(splitmix64(&mut sm))
+    }
+
+    pub fn next_u64(&mut self) -> u64 {
+        let result = self.state[1].wrapping_mul(5).rotate_left(7).wrapping_mul(9);
+        let t = self.state[1] << 17;
+
+        self.state[2] ^= self.state[0];
+        self.state[3] ^= self.state[1];
+        self.state[1] ^= self.state[2];
+        self.state[0] ^= self.state[3];
+        self.state[2] ^= t;
+        self.state[3] = self.state[3].rotate_left(45);
+
+        result
+    }
+
+    // Lemire's multiply-shift with rejection, unbiased for any bound.
+    pub fn below(&mut self, bound: u64) -> u64 {
+        assert!(bound > 0);
+        let threshold = bound.wrapping_neg() % bound;
+        loop {
+            let product = (self.next_u64() as u128) * (bound as u128);
+            if (product as u64) >= threshold {
+                return (product >> 64) as u64;
+            }
+        }
+    }
+
+    pub fn fill_bytes(&mut self, dest: &mut [u8]) {
+        for chunk in dest.chunks_mut(8) {
+            let bytes = self.next_u64().to_le_bytes();
+            chunk.copy_from_slice(&bytes[..chunk.len()]);
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum MessageKind {
+    Uniform,
+    // Printable ASCII, 0x20..=0x7e.
+    Ascii,
+    // Mostly zero bytes with about one bit in 32 set.
+    Sparse,
+    // The message index added to the seed, big-endian in the last bytes.
+    Counter,
+}
+
+impl MessageKind {
+    pub const ALL: [MessageKind; 4] = [
+        MessageKind::Uniform,
+        MessageKind::Ascii,
+        MessageKind::Sparse,
+        MessageKind::Counter,
+    ];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            MessageKind::Uniform => "uniform",
+            MessageKind::Ascii => "ASCII",
+            MessageKind::Sparse => "sparse",
+            MessageKind::Counter => "counter",
+        }
+    }
+
+    // Message `index` depends only on the seed and the index, never on which
+    // thread asks for it or in what order.
+    pub fn fill(self, dest: &mut [u8], seed: u64, index: usize) {
+        let mut rng = Rng::for_stream(seed, index as u64);
+        match self {
+            MessageKind::Uniform => rng.fill_bytes(dest),
+            MessageKind::Ascii => {
+                for byte in dest.iter_mut() {
+                    *byte = 0x20 + rng.below(0x7f - 0x20) as u8;
+                }
+            }
+            MessageKind::Sparse => {
+                dest.fill(0);
+                let bits = dest.len() * 8;
+                for bit in sample_indices(bits, bits.div_ceil(32), &mut rng) {
+                    dest[bit / 8] |= 1 << (bit % 8);
+                }
+            }
+            MessageKind::Counter => {
+                dest.fill(0);
+                let counter = seed.wrapping_add(index as u64).to_be_bytes();
+                let len = dest.len().min(counter.len());
+                let start = dest.len() - len;
+                dest[start..].copy_from_slice(&counter[counter.len() - len..]);
+            }
+        }
+    }
+
+    pub fn message(self, len: usize, seed: u64, index: usize) -> Vec<u8> {
+        let mut message = vec![0u8; len];
+        self.fill(&mut message, seed, index);
+        message
+    }
+}
+
+// Accepts decimal or `0x`-prefixed hexadecimal, as seeds are printed in hex.
+pub fn parse_seed(text: &str) -> Result<u64, String> {
+    let text = text.trim().replace('_', "");
+    let parsed = match text.strip_prefix("0x").or_else(|| text.strip_prefix("0X")) {
+        Some(hex) => u64::from_str_radix(hex, 16),
+        None => text.parse(),
+    };
+    parsed.map_err(|_| format!("invalid seed '{}'", text))
+}
+
+// Floyd's algorithm: `count` distinct indices below `total` without
+// materializing the whole range, returned sorted.
+pub fn sample_indices(total: usize, count: usize, rng: &mut Rng) -> Vec<usize> {
+    if count >= total {
+        return (0..total).collect();
+    }
+    let mut chosen = std::collections::HashSet::with_capacity(count);
+    for j in total - count..total {
+        let t = rng.below(j as u64 + 1) as usize;
+        if !chosen.insert(t) {
+            chosen.insert(j);
+        }
+    }
+    let mut indices: Vec<usize> = chosen.into_iter().collect();
+    indices.sort_unstable();
+    indices
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_same_seed_same_sequence() {
+        let mut a = Rng::new(42);
+        let mut b = Rng::new(42);
+        for _ in 0..100 {
+            assert_eq!(a.next_u64(), b.next_u64());
+        }
+    }
+
+    #[test]
+    fn test_different_streams_differ() {
+        let mut a = Rng::for_stream(42, 0);
+        let mut b = Rng::for_stream(42, 1);
+        assert_ne!(a.next_u64(), b.next_u64());
+    }
+
+    #[test]
+    fn test_fill_bytes_partial_chunk() {
+        let mut rng = Rng::new(7);
+        let mut buf = [0u8; 13];
+        rng.fill_bytes(&mut buf);
+        assert!(buf.iter().any(|&b| b != 0));
+    }
+
+    #[test]
+    fn test_below_stays_in_range() {
+        let mut rng = Rng::new(3);
+        let mut seen = [false; 7];
+        for _ in 0..1000 {
+            let value = rng.below(7) as usize;
+            assert!(value < 7);
+            seen[value] = true;
+        }
+        assert!(seen.iter().all(|&s| s));
+    }
+
+    #[test]
+    fn test_sample_indices_distinct_and_sorted() {
+        let mut rng = Rng::new(11);
+        let indices = sample_indices(1_000_000, 500, &mut rng);
+        assert_eq!(indices.len(), 500);
+        assert!(indices.windows(2).all(|w| w[0] < w[1]));
+        assert!(*indices.last().unwrap() < 1_000_000);
+    }
+
+    #[test]
+    fn test_message_kinds() {
+        let uniform = MessageKind::Uniform.message(13, 5, 2);
+        let mut expected = [0u8; 13];
+        Rng::for_stream(5, 2).fill_bytes(&mut expected);
+        assert_eq!(uniform, expected);
+
+        let ascii = MessageKind::Ascii.message(256, 5, 2);
+        assert!(ascii.iter().all(|b| (0x20..=0x7e).contains(b)));
+
+        let sparse = MessageKind::Sparse.message(64, 5, 2);
+        let ones: u32 = sparse.iter().map(|b| b.count_ones()).sum();
+        assert_eq!(ones, 16);
+
+        assert_eq!(
+            MessageKind::Counter.message(10, 0x0102, 3),
+            [0, 0, 0, 0, 0, 0, 0, 0, 0x01, 0x05]
+        );
+        assert_eq!(MessageKind::Counter.message(2, 0x0102, 3), [0x01, 0x05]);
+
+        for kind in MessageKind::ALL {
+            assert_eq!(kind.message(32, 9, 1), kind.message(32, 9, 1));
+            assert!(kind.message(0, 9, 1).is_empty());
+        }
+        assert_ne!(
+            MessageKind::Uniform.message(32, 9, 1),
+            MessageKind::Uniform.message(32, 9, 2)
+        );
+    }
+
+    #[test]
+    fn test_parse_seed() {
+        assert_eq!(parse_seed("42"), Ok(42));
+        assert_eq!(parse_seed("0x5eed_a1a7_c4e0_0001"), Ok(DEFAULT_SEED));
+        assert_eq!(
+            parse_seed(&format!("{:#x}", DEFAULT_SEED)),
+            Ok(DEFAULT_SEED)
+        );
+        assert!(parse_seed("seed").is_err());
+    }
+
+    #[test]
+    fn test_sample_indices_count_above_total() {
+        let mut rng = Rng::new(11);
+        assert_eq!(sample_indices(5, 10, &mut rng), vec![0, 1, 2, 3, 4]);
+    }
+}
@@ -0,0 +1,169 @@
+use crate::app::*;
+use sac::heatmap_color;
+use statistics::Summary;
+use worker::Progress;
+
+const BAR_WIDTH: usize = 50;
+
+impl App {
+    pub fn switch_to_rounds(&mut self) {
+        self.current_screen = CurrentScreen::Sandbox;
+        self.current_mode = Some(SandboxMode::Rounds);
+        self.input_state = Some(InputState::EnteringText);
+        self.messages.clear();
+        self.colored_messages.clear();
+        self.messages.push("Enter string to hash:".to_string());
+    }
+
+    pub fn process_rounds(&mut self) {
+        let time = std::time::Instant::now();
+        let points = round_profile_with_progress(self.message(), &self.progress);
+        let elapsed = time.elapsed();
+        if self.progress.is_cancelled() {
+            return;
+        }
+
+        self.messages.extend(profile_lines(&points));
+        if !points.is_empty() {
+            self.messages.push("".to_string());
+            self.messages
+                .push("Rounds | average avalanche (50% is ideal)".to_string());
+            for row in profile_rows(&points) {
+                self.add_colored_text_message(&row);
+            }
+        }
+        self.messages
+            .push(format!("Computation time: {:?}", elapsed));
+        self.messages.push("Press Enter to continue...".to_string());
+    }
+}
+
+pub struct RoundPoint {
+    pub rounds: usize,
+    pub summary: Summary,
+}
+
+// Automatic analysis of the in-crate SHA-256 for every round count 1..=64.
+pub fn round_profile(data: &[u8]) -> Vec<RoundPoint> {
+    round_profile_with_progress(data, &Progress::none())
+}
+
+// One step per round count, so a cancelled job stops after the current one.
+pub fn round_profile_with_progress(data: &[u8], progress: &Progress) -> Vec<RoundPoint> {
+    progress.stage("Round counts", sha256::ROUND_VARIANTS.len());
+    sha256::ROUND_VARIANTS
+        .iter()
+        .take_while(|_| progress.step())
+        .filter_map(|variant| {
+            let report = automatic::analyze_auto(variant, data, &sampling::SamplingPlan::default());
+            report.summary().map(|summary| RoundPoint {
+                rounds: variant.rounds(),
+                summary,
+            })
+        })
+        .collect()
+}
+
+// First round count whose mean avalanche is within one percentage point of 50%.
+pub fn rounds_to_full_diffusion(points: &[RoundPoint]) -> Option<usize> {
+    points
+        .iter()
+        .find(|point| (point.summary.mean - 50.0).abs() <= 1.0)
+        .map(|point| point.rounds)
+}
+
+pub fn profile_lines(points: &[RoundPoint]) -> Vec<String> {
+    let mut lines = vec!["=== SHA-256 avalanche by round count ===".to_string()];
+    match points.first() {
+        Some(first) => {
+            lines.push(format!("Flips per round count: {}", first.summary.count));
+            lines.push(match rounds_to_full_diffusion(points) {
+                Some(rounds) => format!("Average within 1% of 50% after {} rounds", rounds),
+                None => "Average never gets within 1% of 50%".to_string(),
+            });
+        }
+        None => lines.push("No bits to flip".to_string()),
+    }
+    lines
+}
+
+pub fn profile_rows(points: &[RoundPoint]) -> Vec<Vec<ColoredText>> {
+    points
+        .iter()
+        .map(|point| {
+            let mean = point.summary.mean;
+            let filled = ((mean / 100.0) * BAR_WIDTH as f64).round() as usize;
+            vec![
+                ColoredText {
+                    text: format!("{:>6} | ", point.rounds),
+                    color: Color::Gray,
+                },
+                ColoredText {
+                    text: "█".repeat(filled),
+                    color: heatmap_color((mean - 50.0).abs(), 1.0, 5.0),
+                },
+                ColoredText {
+                    text: format!(
+                        "{} {:>6.2}%",
+                        " ".repeat(BAR_WIDTH.saturating_sub(filled)),
+                        mean
+                    ),
+                    color: Color::Gray,
+                },
+            ]
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_switch_to_rounds() {
+        let mut app = App::new();
+        app.switch_to_rounds();
+
+        assert!(matches!(app.current_screen, CurrentScreen::Sandbox));
+        assert!(matches!(app.current_mode, Some(SandboxMode::Rounds)));
+        assert!(matches!(app.input_state, Some(InputState::EnteringText)));
+    }
+
+    #[test]
+    fn test_round_profile_climbs_to_half() {
+        let points = round_profile(b"abcd");
+        assert_eq!(points.len(), 64);
+        assert!(points[0].summary.mean < 10.0);
+        assert!((points[63].summary.mean - 50.0).abs() < 5.0);
+
+        let rounds = rounds_to_full_diffusion(&points).unwrap();
+        assert!(rounds > 4 && rounds <= 64);
+    }
+
+    #[test]
+    fn test_process_rounds_draws_bars() {
+        let mut app = App::new();
+        app.original_text = "ab".to_string();
+
+        app.process_rounds();
+
+        assert!(
+            app.messages
+                .iter()
+                .any(|m| m == "=== SHA-256 avalanche by round count ===")
+        );
+        assert_eq!(app.colored_messages.len(), 64);
+        assert_eq!(app.colored_messages[0][0].text, "     1 | ");
+    }
+
+    #[test]
+    fn test_process_rounds_empty_string() {
+        let mut app = App::new();
+        app.original_text = "".to_string();
+
+        app.process_rounds();
+
+        assert!(app.colored_messages.is_empty());
+        assert!(app.messages.iter().any(|m| m == "No bits to flip"));
+    }
+}
@@ -0,0 +1,180 @@
+use crate::app::*;
+use statistics::SacMatrix;
+
+pub const SAC_SAMPLES: usize = 512;
+pub const MAX_MATRIX_MESSAGE_LEN: usize = 1024;
+const HEATMAP_COLUMNS: usize = 64;
+
+impl App {
+    pub fn switch_to_sac(&mut self) {
+        self.current_screen = CurrentScreen::Sandbox;
+        self.current_mode = Some(SandboxMode::Sac);
+        self.input_state = Some(InputState::EnteringText);
+        self.messages.clear();
+        self.colored_messages.clear();
+        self.messages.push("Enter string to hash:".to_string());
+    }
+
+    pub fn process_sac(&mut self) {
+        if self.message().len() > MAX_MATRIX_MESSAGE_LEN {
+            self.messages.push(format!(
+                "Error: SAC analysis supports messages up to {} bytes, got {}",
+                MAX_MATRIX_MESSAGE_LEN,
+                self.message().len()
+            ));
+            self.messages.push("Press Enter to continue...".to_string());
+            return;
+        }
+        let algorithm = self.algorithm();
+        let time = std::time::Instant::now();
+        let sac = SacMatrix::compute_with_progress(
+            algorithm,
+            self.message().len(),
+            SAC_SAMPLES,
+            self.seed,
+            self.message_kind,
+            &self.progress,
+        );
+        let elapsed = time.elapsed();
+        if self.progress.is_cancelled() {
+            return;
+        }
+
+        self.messages.extend(summary_lines(&sac, algorithm));
+        self.messages
+            .push(format!("Computation time: {:?}", elapsed));
+
+        if sac.input_bits > 0 {
+            self.messages.push("".to_string());
+            self.messages.push(
+                "Heatmap (rows: input bits, green < 2 sigma < yellow < 3 sigma < red):".to_string(),
+            );
+            for i in 0..sac.input_bits {
+                let row = sac_heatmap_row(&sac, i);
+                self.add_colored_text_message(&row);
+            }
+        }
+        self.messages.push("Press Enter to continue...".to_string());
+    }
+}
+
+pub fn summary_lines(sac: &SacMatrix, algorithm: &dyn HashAlgorithm) -> Vec<String> {
+    let mut lines = vec![
+        "=== Strict Avalanche Criterion ===".to_string(),
+        format!("Hash function: {}", algorithm.name()),
+        format!(
+            "Matrix: {} input bits x {} output bits",
+            sac.input_bits, sac.output_bits
+        ),
+        format!(
+            "Random messages: {} {} (seed {:#x})",
+            sac.samples,
+            sac.kind.name(),
+            sac.seed
+        ),
+        format!("Mean flip probability: {:.4}", sac.mean_probability()),
+        format!("Mean |P - 0.5|: {:.4}", sac.mean_absolute_deviation()),
+    ];
+    if let Some((i, j, deviation)) = sac.max_deviation() {
+        lines.push(format!(
+            "Max |P - 0.5|: {:.4} (input bit {}, output bit {})",
+            deviation.abs(),
+            i,
+            j
+        ));
+    }
+    lines.push(format!(
+        "Expected noise (1 sigma): {:.4}",
+        sac.expected_noise()
+    ));
+    lines
+}
+
+fn sac_heatmap_row(sac: &SacMatrix, input_bit: usize) -> Vec<ColoredText> {
+    let sigma = sac.expected_noise();
+    let group = sac.output_bits.div_ceil(HEATMAP_COLUMNS).max(1);
+
+    let mut row = vec![ColoredText {
+        text: format!("{:>5} ", input_bit),
+        color: Color::Gray,
+    }];
+    for start in (0..sac.output_bits).step_by(group) {
+        let end = (start + group).min(sac.output_bits);
+        let worst = (start..end)
+            .map(|j| sac.deviation(input_bit, j).abs())
+            .fold(0.0, f64::max);
+        row.push(ColoredText {
+            text: "█".to_string(),
+            color: heatmap_color(worst, 2.0 * sigma, 3.0 * sigma),
+        });
+    }
+    row
+}
+
+pub(crate) fn heatmap_color(value: f64, yellow_from: f64, red_from: f64) -> Color {
+    if value <= yellow_from {
+        Color::Green
+    } else if value <= red_from {
+        Color::Yellow
+    } else {
+        Color::Red
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_switch_to_sac() {
+        let mut app = App::new();
+        app.switch_to_sac();
+
+        assert!(matches!(app.current_screen, CurrentScreen::Sandbox));
+        assert!(matches!(app.current_mode, Some(SandboxMode::Sac)));
+        assert!(matches!(app.input_state, Some(InputState::EnteringText)));
+    }
+
+    #[test]
+    fn test_process_sac_reports_summary_and_heatmap() {
+        let mut app = App::new();
+        app.original_text = "ab".to_string();
+
+        app.process_sac();
+
+        assert!(
+            app.messages
+                .iter()
+                .any(|m| m == "Matrix: 16 input bits x 256 output bits")
+        );
+        assert!(app.messages.iter().any(|m| m.contains("Max |P - 0.5|")));
+        assert_eq!(app.colored_messages.len(), 16);
+        assert_eq!(app.colored_messages[0].len(), 1 + 64);
+    }
+
+    #[test]
+    fn test_process_sac_empty_string() {
+        let mut app = App::new();
+        app.original_text = "".to_string();
+
+        app.process_sac();
+
+        assert!(app.colored_messages.is_empty());
+        assert!(app.messages.iter().any(|m| m.contains("Press Enter")));
+    }
+
+    #[test]
+    fn test_process_sac_rejects_long_messages() {
+        let mut app = App::new();
+        app.input_bytes = Some(vec![0; MAX_MATRIX_MESSAGE_LEN + 1]);
+
+        app.process_sac();
+
+        assert!(
+            app.messages
+                .iter()
+                .any(|m| m.starts_with("Error: SAC analysis supports messages up to"))
+        );
+        assert!(app.colored_messages.is_empty());
+    }
+}
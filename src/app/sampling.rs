@@ -0,0 +1,291 @@
+use crate::app::*;
+use automatic::SAMPLED_FLIPS;
+use std::collections::{HashMap, HashSet};
+
+pub const BLOCK_BYTES: usize = 64;
+// Flips between two checks of the interval width.
+pub const BATCH_FLIPS: usize = 256;
+// Upper bound for a width target, so a noisy hash can't run forever.
+pub const MAX_ADAPTIVE_FLIPS: usize = 1 << 16;
+pub const CONFIDENCE: f64 = 0.95;
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Strata {
+    Uniform,
+    // Positions spread evenly over consecutive runs of this many bytes.
+    Bytes(usize),
+}
+
+impl Strata {
+    pub fn name(&self) -> String {
+        match self {
+            Strata::Uniform => "uniform".to_string(),
+            Strata::Bytes(1) => "stratified by byte".to_string(),
+            Strata::Bytes(bytes) => format!("stratified by {}-byte block", bytes),
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Target {
+    Count(usize),
+    // Width of the confidence interval of the mean, in percentage points.
+    Width(f64),
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct SamplingPlan {
+    pub strata: Strata,
+    pub target: Target,
+    pub seed: u64,
+}
+
+impl SamplingPlan {
+    pub const PRESETS: [SamplingPlan; 4] = [
+        SamplingPlan::new(Strata::Uniform, Target::Count(SAMPLED_FLIPS)),
+        SamplingPlan::new(Strata::Bytes(1), Target::Count(SAMPLED_FLIPS)),
+        SamplingPlan::new(Strata::Bytes(BLOCK_BYTES), Target::Count(SAMPLED_FLIPS)),
+        SamplingPlan::new(Strata::Uniform, Target::Width(0.5)),
+    ];
+
+    pub const fn new(strata: Strata, target: Target) -> SamplingPlan {
+        SamplingPlan {
+            strata,
+            target,
+            seed: random::DEFAULT_SEED,
+        }
+    }
+
+    pub fn name(&self) -> String {
+        match self.target {
+            Target::Count(count) => format!("{}, {} flips", self.strata.name(), count),
+            Target::Width(width) => format!("{}, CI width {}%", self.strata.name(), width),
+        }
+    }
+}
+
+impl Default for SamplingPlan {
+    fn default() -> Self {
+        SamplingPlan::PRESETS[0]
+    }
+}
+
+// Draws distinct bit positions, batch after batch, without materializing
+// the whole range.
+pub struct Sampler {
+    total_bits: usize,
+    strata: Strata,
+    rng: random::Rng,
+    chosen: HashSet<usize>,
+    // Positions chosen so far per stratum, for stratified plans.
+    used: HashMap<usize, usize>,
+}
+
+impl Sampler {
+    pub fn new(total_bits: usize, plan: &SamplingPlan) -> Sampler {
+        Sampler {
+            total_bits,
+            strata: plan.strata,
+            rng: random::Rng::new(plan.seed),
+            chosen: HashSet::new(),
+            used: HashMap::new(),
+        }
+    }
+
+    pub fn remaining(&self) -> usize {
+        self.total_bits - self.chosen.len()
+    }
+
+    pub fn next_batch(&mut self, count: usize) -> Vec<usize> {
+        let count = count.min(self.remaining());
+        let batch = if 2 * count > self.remaining() {
+            // Rejection would stall, so pick among what is left.
+            let left: Vec<usize> = (0..self.total_bits)
+                .filter(|bit| !self.chosen.contains(bit))
+                .collect();
+            random::sample_indices(left.len(), count, &mut self.rng)
+                .into_iter()
+                .map(|i| left[i])
+                .collect()
+        } else {
+            match self.strata {
+                Strata::Uniform if self.chosen.is_empty() => {
+                    random::sample_indices(self.total_bits, count, &mut self.rng)
+                }
+                Strata::Uniform => (0..count)
+                    .map(|_| self.draw_in(0, self.total_bits))
+                    .collect(),
+                Strata::Bytes(bytes) => self.stratified(count, bytes.max(1) * 8),
+            }
+        };
+        for &bit in &batch {
+            self.take(bit);
+        }
+        batch
+    }
+
+    // Systematic allocation: strata are visited at evenly spaced points with
+    // a random start, so each gets count / strata positions, give or take one.
+    fn stratified(&mut self, count: usize, stratum_bits: usize) -> Vec<usize> {
+        let strata = self.total_bits.div_ceil(stratum_bits);
+        let offset = self.rng.below(1 << 20) as f64 / (1 << 20) as f64;
+        let mut batch = Vec::with_capacity(count);
+        for j in 0..count {
+            let stratum =
+                (((j as f64 + offset) * strata as f64 / count as f64) as usize).min(strata - 1);
+            let start = stratum * stratum_bits;
+            let end = (start + stratum_bits).min(self.total_bits);
+            let used = self.used.get(&stratum).copied().unwrap_or(0);
+            let bit = if used == end - start {
+                self.draw_in(0, self.total_bits)
+            } else if 2 * used <= end - start {
+                self.draw_in(start, end)
+            } else {
+                self.draw_free(start, end)
+            };
+            batch.push(bit);
+        }
+        batch
+    }
+
+    // Marks `bit` as chosen; false if it already was.
+    fn take(&mut self, bit: usize) -> bool {
+        if !self.chosen.insert(bit) {
+            return false;
+        }
+        if let Strata::Bytes(bytes) = self.strata {
+            *self.used.entry(bit / (bytes.max(1) * 8)).or_insert(0) += 1;
+        }
+        true
+    }
+
+    // A position in `start..end` not chosen yet, by rejection; the range
+    // must have one, and should be far from full.
+    fn draw_in(&mut self, start: usize, end: usize) -> usize {
+        loop {
+            let bit = start + self.rng.below((end - start) as u64) as usize;
+            if self.take(bit) {
+                return bit;
+            }
+        }
+    }
+
+    // Lists what is left, for a stratum more than half full where rejection
+    // would keep missing.
+    fn draw_free(&mut self, start: usize, end: usize) -> usize {
+        let free: Vec<usize> = (start..end)
+            .filter(|bit| !self.chosen.contains(bit))
+            .collect();
+        let bit = free[self.rng.below(free.len() as u64) as usize];
+        self.take(bit);
+        bit
+    }
+}
+
+impl App {
+    pub fn cycle_sampling(&mut self) {
+        let current = SamplingPlan::PRESETS
+            .iter()
+            .position(|&plan| plan == self.sampling)
+            .unwrap_or(SamplingPlan::PRESETS.len() - 1);
+        self.sampling = SamplingPlan::PRESETS[(current + 1) % SamplingPlan::PRESETS.len()];
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sampler_for(total_bits: usize, strata: Strata) -> Sampler {
+        Sampler::new(total_bits, &SamplingPlan::new(strata, Target::Count(0)))
+    }
+
+    #[test]
+    fn test_batches_are_distinct_until_exhausted() {
+        for strata in [Strata::Uniform, Strata::Bytes(1), Strata::Bytes(4)] {
+            let mut sampler = sampler_for(100, strata);
+            let mut seen = HashSet::new();
+            for _ in 0..4 {
+                for bit in sampler.next_batch(30) {
+                    assert!(bit < 100);
+                    assert!(seen.insert(bit), "{:?} repeated bit {}", strata, bit);
+                }
+            }
+            assert_eq!(seen.len(), 100);
+            assert!(sampler.next_batch(10).is_empty());
+        }
+    }
+
+    #[test]
+    fn test_stratified_covers_every_stratum_evenly() {
+        // 1000 bytes, 2000 positions: two per byte.
+        let mut sampler = sampler_for(8000, Strata::Bytes(1));
+        let batch = sampler.next_batch(2000);
+        let mut per_byte = vec![0; 1000];
+        for bit in batch {
+            per_byte[bit / 8] += 1;
+        }
+        assert!(per_byte.iter().all(|&n| n == 2));
+
+        // Fewer positions than blocks: no block gets two.
+        let mut sampler = sampler_for(64 * 8 * 100, Strata::Bytes(64));
+        let mut per_block = vec![0; 100];
+        for bit in sampler.next_batch(50) {
+            per_block[bit / 512] += 1;
+        }
+        assert!(per_block.iter().all(|&n| n <= 1));
+    }
+
+    #[test]
+    fn test_huge_strata_are_drawn_by_rejection() {
+        // One 2 MiB block: listing its free positions per draw took minutes.
+        let bits = 2 << 23;
+        let start = std::time::Instant::now();
+        let mut sampler = sampler_for(bits, Strata::Bytes(2 << 20));
+        let batch = sampler.next_batch(2000);
+        assert_eq!(batch.iter().collect::<HashSet<_>>().len(), 2000);
+        assert!(start.elapsed() < std::time::Duration::from_secs(5));
+
+        // Past half full, strata hand out what is left of them: 100 blocks
+        // of 64 bits, filled to 35 positions each.
+        let mut sampler = sampler_for(6400, Strata::Bytes(8));
+        let mut seen: HashSet<usize> = sampler.next_batch(3000).into_iter().collect();
+        for _ in 0..5 {
+            seen.extend(sampler.next_batch(100));
+        }
+        assert_eq!(seen.len(), 3500);
+        let mut per_block = vec![0; 100];
+        for bit in seen {
+            per_block[bit / 64] += 1;
+        }
+        assert!(per_block.iter().all(|&n| n == 35));
+    }
+
+    #[test]
+    fn test_first_uniform_batch_matches_sample_indices() {
+        let plan = SamplingPlan {
+            seed: 7,
+            ..SamplingPlan::default()
+        };
+        let mut sampler = Sampler::new(8192, &plan);
+        let expected = random::sample_indices(8192, 100, &mut random::Rng::new(7));
+        assert_eq!(sampler.next_batch(100), expected);
+    }
+
+    #[test]
+    fn test_plan_names_and_cycle() {
+        assert_eq!(SamplingPlan::PRESETS[0].name(), "uniform, 4096 flips");
+        assert_eq!(
+            SamplingPlan::PRESETS[2].name(),
+            "stratified by 64-byte block, 4096 flips"
+        );
+        assert_eq!(SamplingPlan::PRESETS[3].name(), "uniform, CI width 0.5%");
+
+        let mut app = App::new();
+        assert_eq!(app.sampling, SamplingPlan::PRESETS[0]);
+        for preset in SamplingPlan::PRESETS.iter().cycle().skip(1).take(4) {
+            app.cycle_sampling();
+            assert_eq!(app.sampling, *preset);
+        }
+    }
+}
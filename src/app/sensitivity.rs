@@ -0,0 +1,292 @@
+use crate::app::*;
+use automatic::{AutomaticReport, FlipOutcome};
+use sac::heatmap_color;
+
+pub const EXTREMES: usize = 5;
+pub const MAX_TABLE_BYTES: usize = 32;
+pub const SPARKLINE_WIDTH: usize = 64;
+const LEVELS: [char; 8] = ['▁', '▂', '▃', '▄', '▅', '▆', '▇', '█'];
+
+// Where a flipped bit sits in the message; `character` is the index and
+// value of the char containing the byte when the input is text.
+pub struct Position {
+    pub byte: usize,
+    pub bit_in_byte: usize,
+    pub character: Option<(usize, char)>,
+}
+
+pub fn locate(bit: usize, input_len: usize, text: Option<&str>) -> Position {
+    let byte = input_len - 1 - bit / 8;
+    let character = text.and_then(|text| {
+        text.char_indices()
+            .enumerate()
+            .take_while(|(_, (offset, _))| *offset <= byte)
+            .last()
+            .map(|(index, (_, c))| (index, c))
+    });
+    Position {
+        byte,
+        bit_in_byte: bit % 8,
+        character,
+    }
+}
+
+fn describe(flip: &FlipOutcome, data: &[u8], text: Option<&str>) -> String {
+    let position = locate(flip.bit_index, data.len(), text);
+    let owner = match position.character {
+        Some((index, c)) => format!("{:?} at char {}", c, index),
+        None => format!("{:#04x}", data[position.byte]),
+    };
+    format!(
+        "bit {} = byte {} bit {} of {}",
+        flip.bit_index, position.byte, position.bit_in_byte, owner
+    )
+}
+
+// Distance from the ideal mean in standard deviations of Binomial(n, 0.5).
+fn deviation(report: &AutomaticReport, percent: f64) -> f64 {
+    let ideal = report.ideal();
+    let bits = percent * ideal.trials as f64 / 100.0;
+    (bits - ideal.mean()).abs() / ideal.std_dev()
+}
+
+fn cell_color(report: &AutomaticReport, percent: f64) -> Color {
+    heatmap_color(deviation(report, percent), 2.0, 3.0)
+}
+
+pub fn sensitivity_lines(report: &AutomaticReport, data: &[u8], text: Option<&str>) -> Vec<String> {
+    if report.flips.is_empty() {
+        return Vec::new();
+    }
+    let mut ranked: Vec<&FlipOutcome> = report.flips.iter().collect();
+    ranked.sort_by(|a, b| a.percent.total_cmp(&b.percent));
+    let count = EXTREMES.min(ranked.len());
+    let format = |flip: &&FlipOutcome| {
+        format!(
+            "  {}: {:.2}% ({} of {} bits)",
+            describe(flip, data, text),
+            flip.percent,
+            flip.hamming_distance,
+            report.algorithm.output_bits()
+        )
+    };
+
+    let mut lines = vec!["=== Per-input-bit sensitivity ===".to_string()];
+    lines.push("Weakest input bits:".to_string());
+    lines.extend(ranked[..count].iter().map(format));
+    lines.push("Strongest input bits:".to_string());
+    lines.extend(ranked[ranked.len() - count..].iter().rev().map(format));
+    lines
+}
+
+// One row per message byte, most significant bit first, with the byte's mean.
+pub fn table_rows(
+    report: &AutomaticReport,
+    data: &[u8],
+    text: Option<&str>,
+) -> Vec<Vec<ColoredText>> {
+    let mut bytes: Vec<(usize, [Option<f64>; 8])> = Vec::new();
+    for flip in report.flips.iter().rev() {
+        let position = locate(flip.bit_index, data.len(), text);
+        if bytes.last().is_none_or(|(byte, _)| *byte != position.byte) {
+            bytes.push((position.byte, [None; 8]));
+        }
+        bytes.last_mut().unwrap().1[7 - position.bit_in_byte] = Some(flip.percent);
+    }
+
+    let mut rows = Vec::new();
+    for (byte, cells) in bytes.iter().take(MAX_TABLE_BYTES) {
+        let label = match locate(8 * (data.len() - 1 - byte), data.len(), text).character {
+            Some((_, c)) => format!("{:?}", c),
+            None => format!("{:#04x}", data[*byte]),
+        };
+        let mut row = vec![ColoredText {
+            text: format!("{:>5} {:<6}|", byte, label),
+            color: Color::Gray,
+        }];
+        for cell in cells {
+            row.push(match cell {
+                Some(percent) => ColoredText {
+                    text: format!("{:>6.1}", percent),
+                    color: cell_color(report, *percent),
+                },
+                None => ColoredText {
+                    text: format!("{:>6}", "-"),
+                    color: Color::DarkGray,
+                },
+            });
+        }
+        let tested: Vec<f64> = cells.iter().flatten().copied().collect();
+        let mean = tested.iter().sum::<f64>() / tested.len() as f64;
+        row.push(ColoredText {
+            text: format!(" | {:>5.1}%", mean),
+            color: Color::Gray,
+        });
+        rows.push(row);
+    }
+    if bytes.len() > MAX_TABLE_BYTES {
+        rows.push(vec![ColoredText {
+            text: format!("  ... {} more bytes", bytes.len() - MAX_TABLE_BYTES),
+            color: Color::Gray,
+        }]);
+    }
+    rows
+}
+
+pub fn table_header() -> String {
+    let bits: String = (0..8)
+        .rev()
+        .map(|bit| format!("{:>6}", format!("b{}", bit)))
+        .collect();
+    format!("{:>5} {:<6}|{} |  mean", "Byte", "", bits)
+}
+
+// Flips in message order, averaged into at most `SPARKLINE_WIDTH` columns and
+// scaled between the lowest and highest column.
+pub fn sparkline(report: &AutomaticReport) -> Option<(String, Vec<ColoredText>)> {
+    if report.flips.is_empty() {
+        return None;
+    }
+    let percents: Vec<f64> = report.flips.iter().rev().map(|f| f.percent).collect();
+    let per_column = percents.len().div_ceil(SPARKLINE_WIDTH);
+    let columns: Vec<f64> = percents
+        .chunks(per_column)
+        .map(|chunk| chunk.iter().sum::<f64>() / chunk.len() as f64)
+        .collect();
+    let min = columns.iter().copied().fold(f64::INFINITY, f64::min);
+    let max = columns.iter().copied().fold(f64::NEG_INFINITY, f64::max);
+
+    let row = columns
+        .iter()
+        .map(|&value| {
+            let level = if max > min {
+                ((value - min) / (max - min) * (LEVELS.len() - 1) as f64).round() as usize
+            } else {
+                LEVELS.len() / 2
+            };
+            ColoredText {
+                text: LEVELS[level].to_string(),
+                color: cell_color(report, value),
+            }
+        })
+        .collect();
+    let caption = format!(
+        "Along the message, {} bit{} per column ({} {:.1}% .. {} {:.1}%):",
+        per_column,
+        if per_column == 1 { "" } else { "s" },
+        LEVELS[0],
+        min,
+        LEVELS[LEVELS.len() - 1],
+        max
+    );
+    Some((caption, row))
+}
+
+impl App {
+    pub(crate) fn push_sensitivity(&mut self, report: &AutomaticReport) {
+        let text = self
+            .input_bytes
+            .is_none()
+            .then_some(self.original_text.as_str());
+        let lines = sensitivity_lines(report, self.message(), text);
+        if lines.is_empty() {
+            return;
+        }
+        let rows = table_rows(report, self.message(), text);
+        self.messages.extend(lines);
+
+        if let Some((caption, row)) = sparkline(report) {
+            self.messages.push(caption);
+            self.add_colored_text_message(&row);
+        }
+        self.messages.push(table_header());
+        for row in rows {
+            self.add_colored_text_message(&row);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_locate_maps_bits_to_bytes_and_chars() {
+        let text = "aé!";
+        let bytes = text.len();
+        assert_eq!(bytes, 4);
+
+        let last = locate(0, bytes, Some(text));
+        assert_eq!((last.byte, last.bit_in_byte), (3, 0));
+        assert_eq!(last.character, Some((2, '!')));
+
+        let continuation = locate(8 + 7, bytes, Some(text));
+        assert_eq!(continuation.byte, 2);
+        assert_eq!(continuation.bit_in_byte, 7);
+        assert_eq!(continuation.character, Some((1, 'é')));
+
+        assert!(locate(31, bytes, None).character.is_none());
+    }
+
+    #[test]
+    fn test_extremes_and_table() {
+        let report = automatic::analyze(algorithms::find("FNV-1a-32").unwrap(), b"abc");
+        let lines = sensitivity_lines(&report, b"abc", Some("abc"));
+        assert_eq!(lines[0], "=== Per-input-bit sensitivity ===");
+        assert_eq!(lines.len(), 3 + 2 * EXTREMES);
+
+        let weakest = &lines[2];
+        let lowest = report
+            .flips
+            .iter()
+            .map(|f| f.percent)
+            .fold(f64::INFINITY, f64::min);
+        assert!(weakest.contains(&format!("{:.2}%", lowest)));
+
+        let rows = table_rows(&report, b"abc", Some("abc"));
+        assert_eq!(rows.len(), 3);
+        assert_eq!(rows[0][0].text, "    0 'a'   |");
+        assert_eq!(rows[0].len(), 10);
+    }
+
+    #[test]
+    fn test_table_marks_untested_bits_and_truncates() {
+        let data = vec![0u8; 100];
+        let report = automatic::analyze_sampled(algorithms::ALGORITHMS[0], &data, 40, 3);
+        let rows = table_rows(&report, &data, None);
+        assert!(rows.iter().flatten().any(|cell| cell.text.trim() == "-"));
+
+        let full = automatic::analyze(algorithms::ALGORITHMS[0], &data);
+        let rows = table_rows(&full, &data, None);
+        assert_eq!(rows.len(), MAX_TABLE_BYTES + 1);
+        assert_eq!(rows[0][0].text, "    0 0x00  |");
+        assert_eq!(rows[MAX_TABLE_BYTES][0].text, "  ... 68 more bytes");
+    }
+
+    #[test]
+    fn test_sparkline_buckets() {
+        let data = vec![7u8; 20];
+        let report = automatic::analyze(algorithms::ALGORITHMS[0], &data);
+        let (caption, row) = sparkline(&report).unwrap();
+        assert_eq!(row.len(), 160usize.div_ceil(3));
+        assert!(caption.starts_with("Along the message, 3 bits per column"));
+        assert!(row.iter().any(|c| c.text == "▁"));
+        assert!(row.iter().any(|c| c.text == "█"));
+    }
+
+    #[test]
+    fn test_process_automatic_includes_sensitivity() {
+        let mut app = App::new();
+        app.original_text = "hey".to_string();
+
+        app.process_automatic();
+
+        assert!(
+            app.messages
+                .iter()
+                .any(|m| m == "=== Per-input-bit sensitivity ===")
+        );
+        // Sparkline and table, then the 256-bit bias strip.
+        assert_eq!(app.colored_messages.len(), 1 + 3 + 4);
+    }
+}
@@ -0,0 +1,301 @@
+use crate::app::algorithms::HashAlgorithm;
+
+const K: [u32; 64] = [
+    0x428a2f98, 0x71374491, 0xb5c0fbcf, 0xe9b5dba5, 0x3956c25b, 0x59f111f1, 0x923f82a4, 0xab1c5ed5,
+    0xd807aa98, 0x12835b01, 0x243185be, 0x550c7dc3, 0x72be5d74, 0x80deb1fe, 0x9bdc06a7, 0xc19bf174,
+    0xe49b69c1, 0xefbe4786, 0x0fc19dc6, 0x240ca1cc, 0x2de92c6f, 0x4a7484aa, 0x5cb0a9dc, 0x76f988da,
+    0x983e5152, 0xa831c66d, 0xb00327c8, 0xbf597fc7, 0xc6e00bf3, 0xd5a79147, 0x06ca6351, 0x14292967,
+    0x27b70a85, 0x2e1b2138, 0x4d2c6dfc, 0x53380d13, 0x650a7354, 0x766a0abb, 0x81c2c92e, 0x92722c85,
+    0xa2bfe8a1, 0xa81a664b, 0xc24b8b70, 0xc76c51a3, 0xd192e819, 0xd6990624, 0xf40e3585, 0x106aa070,
+    0x19a4c116, 0x1e376c08, 0x2748774c, 0x34b0bcb5, 0x391c0cb3, 0x4ed8aa4a, 0x5b9cca4f, 0x682e6ff3,
+    0x748f82ee, 0x78a5636f, 0x84c87814, 0x8cc70208, 0x90befffa, 0xa4506ceb, 0xbef9a3f7, 0xc67178f2,
+];
+
+pub const INITIAL_STATE: [u32; 8] = [
+    0x6a09e667, 0xbb67ae85, 0x3c6ef372, 0xa54ff53a, 0x510e527f, 0x9b05688c, 0x1f83d9ab, 0x5be0cd19,
+];
+
+pub const MAX_ROUNDS: usize = 64;
+
+static NAMES: [&str; MAX_ROUNDS] = [
+    "SHA-256-R1",
+    "SHA-256-R2",
+    "SHA-256-R3",
+    "SHA-256-R4",
+    "SHA-256-R5",
+    "SHA-256-R6",
+    "SHA-256-R7",
+    "SHA-256-R8",
+    "SHA-256-R9",
+    "SHA-256-R10",
+    "SHA-256-R11",
+    "SHA-256-R12",
+    "SHA-256-R13",
+    "SHA-256-R14",
+    "SHA-256-R15",
+    "SHA-256-R16",
+    "SHA-256-R17",
+    "SHA-256-R18",
+    "SHA-256-R19",
+    "SHA-256-R20",
+    "SHA-256-R21",
+    "SHA-256-R22",
+    "SHA-256-R23",
+    "SHA-256-R24",
+    "SHA-256-R25",
+    "SHA-256-R26",
+    "SHA-256-R27",
+    "SHA-256-R28",
+    "SHA-256-R29",
+    "SHA-256-R30",
+    "SHA-256-R31",
+    "SHA-256-R32",
+    "SHA-256-R33",
+    "SHA-256-R34",
+    "SHA-256-R35",
+    "SHA-256-R36",
+    "SHA-256-R37",
+    "SHA-256-R38",
+    "SHA-256-R39",
+    "SHA-256-R40",
+    "SHA-256-R41",
+    "SHA-256-R42",
+    "SHA-256-R43",
+    "SHA-256-R44",
+    "SHA-256-R45",
+    "SHA-256-R46",
+    "SHA-256-R47",
+    "SHA-256-R48",
+    "SHA-256-R49",
+    "SHA-256-R50",
+    "SHA-256-R51",
+    "SHA-256-R52",
+    "SHA-256-R53",
+    "SHA-256-R54",
+    "SHA-256-R55",
+    "SHA-256-R56",
+    "SHA-256-R57",
+    "SHA-256-R58",
+    "SHA-256-R59",
+    "SHA-256-R60",
+    "SHA-256-R61",
+    "SHA-256-R62",
+    "SHA-256-R63",
+    "SHA-256-R64",
+];
+
+fn message_schedule(block: &[u8]) -> [u32; 64] {
+    let mut w = [0u32; 64];
+    for (i, word) in block.chunks_exact(4).enumerate() {
+        w[i] = u32::from_be_bytes([word[0], word[1], word[2], word[3]]);
+    }
+    for i in 16..64 {
+        let s0 = w[i - 15].rotate_right(7) ^ w[i - 15].rotate_right(18) ^ (w[i - 15] >> 3);
+        let s1 = w[i - 2].rotate_right(17) ^ w[i - 2].rotate_right(19) ^ (w[i - 2] >> 10);
+        w[i] = w[i - 16]
+            .wrapping_add(s0)
+            .wrapping_add(w[i - 7])
+            .wrapping_add(s1);
+    }
+    w
+}
+
+// One round on the working variables a..h.
+fn round(v: &mut [u32; 8], k: u32, w: u32) {
+    let [a, b, c, d, e, f, g, h] = *v;
+    let s1 = e.rotate_right(6) ^ e.rotate_right(11) ^ e.rotate_right(25);
+    let ch = (e & f) ^ (!e & g);
+    let t1 = h
+        .wrapping_add(s1)
+        .wrapping_add(ch)
+        .wrapping_add(k)
+        .wrapping_add(w);
+    let s0 = a.rotate_right(2) ^ a.rotate_right(13) ^ a.rotate_right(22);
+    let maj = (a & b) ^ (a & c) ^ (b & c);
+    let t2 = s0.wrapping_add(maj);
+    *v = [t1.wrapping_add(t2), a, b, c, d.wrapping_add(t1), e, f, g];
+}
+
+// Compression function truncated to the first `rounds` rounds, with the
+// usual feed-forward of the chaining value.
+pub fn compress(state: &mut [u32; 8], block: &[u8], rounds: usize) {
+    let w = message_schedule(block);
+    let mut v = *state;
+    for i in 0..rounds.min(MAX_ROUNDS) {
+        round(&mut v, K[i], w[i]);
+    }
+    for (s, v) in state.iter_mut().zip(v) {
+        *s = s.wrapping_add(v);
+    }
+}
+
+// Working variables a..h before the first round and after each round.
+pub fn compress_trace(state: &[u32; 8], block: &[u8]) -> Vec<[u32; 8]> {
+    let w = message_schedule(block);
+    let mut v = *state;
+    let mut trace = Vec::with_capacity(MAX_ROUNDS + 1);
+    trace.push(v);
+    for i in 0..MAX_ROUNDS {
+        round(&mut v, K[i], w[i]);
+        trace.push(v);
+    }
+    trace
+}
+
+// One full-round trace per padded block, chained as in `digest`.
+pub fn message_trace(data: &[u8]) -> Vec<Vec<[u32; 8]>> {
+    let mut state = INITIAL_STATE;
+    pad(data)
+        .chunks_exact(64)
+        .map(|block| {
+            let trace = compress_trace(&state, block);
+            compress(&mut state, block, MAX_ROUNDS);
+            trace
+        })
+        .collect()
+}
+
+pub fn pad(data: &[u8]) -> Vec<u8> {
+    let mut padded = data.to_vec();
+    padded.push(0x80);
+    while padded.len() % 64 != 56 {
+        padded.push(0);
+    }
+    padded.extend_from_slice(&((data.len() as u64).wrapping_mul(8)).to_be_bytes());
+    padded
+}
+
+pub fn digest(data: &[u8], rounds: usize) -> [u8; 32] {
+    let mut state = INITIAL_STATE;
+    for block in pad(data).chunks_exact(64) {
+        compress(&mut state, block, rounds);
+    }
+
+    let mut out = [0u8; 32];
+    for (chunk, word) in out.chunks_exact_mut(4).zip(state) {
+        chunk.copy_from_slice(&word.to_be_bytes());
+    }
+    out
+}
+
+#[derive(Clone, Copy)]
+pub struct ReducedSha256 {
+    rounds: usize,
+    name: &'static str,
+}
+
+impl ReducedSha256 {
+    pub fn rounds(&self) -> usize {
+        self.rounds
+    }
+}
+
+impl HashAlgorithm for ReducedSha256 {
+    fn name(&self) -> &'static str {
+        self.name
+    }
+
+    fn output_bits(&self) -> usize {
+        256
+    }
+
+    fn hash(&self, data: &[u8]) -> Vec<u8> {
+        digest(data, self.rounds).to_vec()
+    }
+}
+
+// ROUND_VARIANTS[r - 1] runs r rounds.
+pub static ROUND_VARIANTS: [ReducedSha256; MAX_ROUNDS] = {
+    let mut variants = [ReducedSha256 {
+        rounds: 0,
+        name: "",
+    }; MAX_ROUNDS];
+    let mut i = 0;
+    while i < MAX_ROUNDS {
+        variants[i] = ReducedSha256 {
+            rounds: i + 1,
+            name: NAMES[i],
+        };
+        i += 1;
+    }
+    variants
+};
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use sha2::Digest;
+
+    #[test]
+    fn test_full_rounds_match_sha256() {
+        for input in [
+            &b""[..],
+            b"abc",
+            &[0xa5; 55],
+            &[0x5a; 56],
+            &[7; 64],
+            &[1; 1000],
+        ] {
+            assert_eq!(
+                digest(input, MAX_ROUNDS).to_vec(),
+                sha2::Sha256::digest(input).to_vec()
+            );
+        }
+    }
+
+    #[test]
+    fn test_pad_lengths() {
+        assert_eq!(pad(b"").len(), 64);
+        assert_eq!(pad(&[0; 55]).len(), 64);
+        assert_eq!(pad(&[0; 56]).len(), 128);
+        assert_eq!(&pad(b"abc")[61..], &[0, 0, 24]);
+    }
+
+    #[test]
+    fn test_fewer_rounds_change_the_digest() {
+        assert_ne!(digest(b"abc", 63), digest(b"abc", 64));
+        assert_ne!(digest(b"abc", 1), digest(b"abc", 2));
+    }
+
+    #[test]
+    fn test_zero_rounds_is_feed_forward_only() {
+        let mut state = INITIAL_STATE;
+        compress(&mut state, &[0; 64], 0);
+        for (s, h) in state.iter().zip(INITIAL_STATE) {
+            assert_eq!(*s, h.wrapping_add(h));
+        }
+    }
+
+    #[test]
+    fn test_compress_trace_matches_compress() {
+        let block = &pad(b"abc")[..64];
+        let trace = compress_trace(&INITIAL_STATE, block);
+        assert_eq!(trace.len(), MAX_ROUNDS + 1);
+        assert_eq!(trace[0], INITIAL_STATE);
+
+        for rounds in [1, 10, 64] {
+            let mut state = INITIAL_STATE;
+            compress(&mut state, block, rounds);
+            for i in 0..8 {
+                assert_eq!(state[i], INITIAL_STATE[i].wrapping_add(trace[rounds][i]));
+            }
+        }
+    }
+
+    #[test]
+    fn test_message_trace_one_per_block() {
+        assert_eq!(message_trace(b"abc").len(), 1);
+        assert_eq!(message_trace(&[0; 100]).len(), 2);
+        assert_eq!(message_trace(&[0; 100])[0][0], INITIAL_STATE);
+        assert_ne!(message_trace(&[0; 100])[1][0], INITIAL_STATE);
+    }
+
+    #[test]
+    fn test_round_variants() {
+        assert_eq!(ROUND_VARIANTS[0].rounds(), 1);
+        assert_eq!(ROUND_VARIANTS[0].name(), "SHA-256-R1");
+        assert_eq!(ROUND_VARIANTS[63].rounds(), 64);
+        assert_eq!(ROUND_VARIANTS[63].name(), "SHA-256-R64");
+        assert_eq!(ROUND_VARIANTS[7].hash(b"abc"), digest(b"abc", 8).to_vec());
+    }
+}
@@ -1,150 +1,1160 @@
 use crate::app::*;
+use random::MessageKind;
+use rayon::prelude::*;
+use serde::Serialize;
+use worker::Progress;
 
-pub fn percent_difference(s1: &str, s2: &str) -> (f64, Vec<ColoredText>, Vec<ColoredText>) {
-    let chars1: Vec<char> = s1.chars().collect();
-    let chars2: Vec<char> = s2.chars().collect();
-
-    if chars1.len() != chars2.len() {
-        return (0.0, Vec::new(), Vec::new());
-    }
-
-    let mut older = Vec::new();
-    let mut newer = Vec::new();
-    let mut diff_count = 0;
-
-    for i in 0..chars1.len() {
-        if chars1[i] != chars2[i] {
-            diff_count += 1;
-            older.push(ColoredText {
-                text: chars1[i].to_string(),
-                color: Color::Red,
-            });
-            newer.push(ColoredText {
-                text: chars2[i].to_string(),
-                color: Color::Yellow,
-            });
+#[derive(Serialize)]
+pub struct Summary {
+    pub count: usize,
+    pub min: f64,
+    pub max: f64,
+    pub mean: f64,
+    pub variance: f64,
+    pub std_dev: f64,
+    pub median: f64,
+    pub q1: f64,
+    pub q3: f64,
+    pub p1: f64,
+    pub p5: f64,
+    pub p95: f64,
+    pub p99: f64,
+    pub skewness: f64,
+    pub kurtosis: f64,
+}
+
+impl Summary {
+    pub fn from_values(values: &[f64]) -> Option<Summary> {
+        if values.is_empty() {
+            return None;
+        }
+
+        let mut sorted = values.to_vec();
+        sorted.sort_by(f64::total_cmp);
+
+        let count = sorted.len();
+        let n = count as f64;
+        let mean = sorted.iter().sum::<f64>() / n;
+        let central_moment = |k: i32| sorted.iter().map(|v| (v - mean).powi(k)).sum::<f64>() / n;
+        let variance = central_moment(2);
+        let std_dev = variance.sqrt();
+
+        // Population moments; a constant sample has no defined shape, report 0.
+        let (skewness, kurtosis) = if variance > 0.0 {
+            (
+                central_moment(3) / variance.powf(1.5),
+                central_moment(4) / (variance * variance) - 3.0,
+            )
         } else {
-            older.push(ColoredText {
-                text: chars1[i].to_string(),
-                color: Color::Green,
-            });
-            newer.push(ColoredText {
-                text: chars2[i].to_string(),
-                color: Color::Green,
-            });
+            (0.0, 0.0)
+        };
+
+        Some(Summary {
+            count,
+            min: sorted[0],
+            max: sorted[count - 1],
+            mean,
+            variance,
+            std_dev,
+            median: percentile(&sorted, 50.0),
+            q1: percentile(&sorted, 25.0),
+            q3: percentile(&sorted, 75.0),
+            p1: percentile(&sorted, 1.0),
+            p5: percentile(&sorted, 5.0),
+            p95: percentile(&sorted, 95.0),
+            p99: percentile(&sorted, 99.0),
+            skewness,
+            kurtosis,
+        })
+    }
+}
+
+// Linear interpolation between closest ranks; `sorted` must be ascending and non-empty.
+pub fn percentile(sorted: &[f64], p: f64) -> f64 {
+    let rank = (p / 100.0).clamp(0.0, 1.0) * (sorted.len() - 1) as f64;
+    let lower = rank.floor() as usize;
+    let upper = rank.ceil() as usize;
+    sorted[lower] + (sorted[upper] - sorted[lower]) * (rank - lower as f64)
+}
+
+pub struct Binomial {
+    pub trials: usize,
+    pub p: f64,
+}
+
+impl Binomial {
+    pub fn ideal(output_bits: usize) -> Binomial {
+        Binomial {
+            trials: output_bits,
+            p: 0.5,
         }
     }
 
-    let percent = if chars1.is_empty() {
-        0.0
+    pub fn mean(&self) -> f64 {
+        self.trials as f64 * self.p
+    }
+
+    pub fn variance(&self) -> f64 {
+        self.trials as f64 * self.p * (1.0 - self.p)
+    }
+
+    pub fn std_dev(&self) -> f64 {
+        self.variance().sqrt()
+    }
+
+    pub fn pmf(&self, k: usize) -> f64 {
+        if k > self.trials {
+            return 0.0;
+        }
+        if self.p <= 0.0 || self.p >= 1.0 {
+            let certain = if self.p <= 0.0 { 0 } else { self.trials };
+            return if k == certain { 1.0 } else { 0.0 };
+        }
+        let n = self.trials as f64;
+        let k = k as f64;
+        let ln_choose = ln_gamma(n + 1.0) - ln_gamma(k + 1.0) - ln_gamma(n - k + 1.0);
+        (ln_choose + k * self.p.ln() + (n - k) * (1.0 - self.p).ln()).exp()
+    }
+}
+
+pub struct GoodnessOfFit {
+    pub test: &'static str,
+    pub statistic: f64,
+    pub degrees_of_freedom: Option<usize>,
+    pub p_value: f64,
+}
+
+impl GoodnessOfFit {
+    pub fn passes(&self, significance_level: f64) -> bool {
+        self.p_value >= significance_level
+    }
+}
+
+// Pearson's chi-square test; tail bins are pooled until every expected count is at least 5.
+pub fn chi_square_test(distances: &[usize], reference: &Binomial) -> Option<GoodnessOfFit> {
+    let total = distances.len() as f64;
+    let mut observed = vec![0usize; reference.trials + 1];
+    for &d in distances {
+        observed[d.min(reference.trials)] += 1;
+    }
+
+    let mut bins: Vec<(f64, f64)> = Vec::new();
+    let mut pending = (0.0, 0.0);
+    for (k, &count) in observed.iter().enumerate() {
+        pending.0 += count as f64;
+        pending.1 += total * reference.pmf(k);
+        if pending.1 >= 5.0 {
+            bins.push(pending);
+            pending = (0.0, 0.0);
+        }
+    }
+    if let Some(last) = bins.last_mut() {
+        last.0 += pending.0;
+        last.1 += pending.1;
+    }
+    if bins.len() < 2 {
+        return None;
+    }
+
+    let statistic = bins.iter().map(|(o, e)| (o - e) * (o - e) / e).sum::<f64>();
+    let degrees_of_freedom = bins.len() - 1;
+
+    Some(GoodnessOfFit {
+        test: "Chi-square",
+        statistic,
+        degrees_of_freedom: Some(degrees_of_freedom),
+        p_value: chi_square_survival(statistic, degrees_of_freedom as f64),
+    })
+}
+
+// One-sample Kolmogorov-Smirnov test. The asymptotic p-value is conservative for a
+// discrete reference distribution.
+pub fn ks_test(distances: &[usize], reference: &Binomial) -> Option<GoodnessOfFit> {
+    if distances.is_empty() {
+        return None;
+    }
+    let total = distances.len() as f64;
+    let mut observed = vec![0usize; reference.trials + 1];
+    for &d in distances {
+        observed[d.min(reference.trials)] += 1;
+    }
+
+    let mut empirical = 0.0;
+    let mut expected = 0.0;
+    let mut statistic: f64 = 0.0;
+    for (k, &count) in observed.iter().enumerate() {
+        empirical += count as f64 / total;
+        expected += reference.pmf(k);
+        statistic = statistic.max((empirical - expected).abs());
+    }
+
+    let sqrt_n = total.sqrt();
+    let lambda = (sqrt_n + 0.12 + 0.11 / sqrt_n) * statistic;
+
+    Some(GoodnessOfFit {
+        test: "Kolmogorov-Smirnov",
+        statistic,
+        degrees_of_freedom: None,
+        p_value: kolmogorov_survival(lambda),
+    })
+}
+
+// Lanczos approximation (g = 7, n = 9).
+pub fn ln_gamma(x: f64) -> f64 {
+    const COEFFICIENTS: [f64; 9] = [
+        0.999_999_999_999_809_9,
+        676.520_368_121_885_1,
+        -1_259.139_216_722_402_8,
+        771.323_428_777_653_1,
+        -176.615_029_162_140_6,
+        12.507_343_278_686_905,
+        -0.138_571_095_265_720_12,
+        9.984_369_578_019_572e-6,
+        1.505_632_735_149_311_6e-7,
+    ];
+    if x < 0.5 {
+        let pi = std::f64::consts::PI;
+        return (pi / (pi * x).sin()).ln() - ln_gamma(1.0 - x);
+    }
+    let x = x - 1.0;
+    let t = x + 7.5;
+    let series = COEFFICIENTS[1..]
+        .iter()
+        .enumerate()
+        .fold(COEFFICIENTS[0], |acc, (i, c)| {
+            acc + c / (x + i as f64 + 1.0)
+        });
+    0.5 * (2.0 * std::f64::consts::PI).ln() + (x + 0.5) * t.ln() - t + series.ln()
+}
+
+// Regularized upper incomplete gamma function Q(a, x).
+pub fn regularized_gamma_q(a: f64, x: f64) -> f64 {
+    if x <= 0.0 {
+        return 1.0;
+    }
+    let ln_prefix = a * x.ln() - x - ln_gamma(a);
+    if x < a + 1.0 {
+        let mut term = 1.0 / a;
+        let mut sum = term;
+        let mut denominator = a;
+        for _ in 0..1000 {
+            denominator += 1.0;
+            term *= x / denominator;
+            sum += term;
+            if term.abs() < sum.abs() * 1e-15 {
+                break;
+            }
+        }
+        (1.0 - sum * ln_prefix.exp()).clamp(0.0, 1.0)
+    } else {
+        // Lentz's continued fraction.
+        let tiny = 1e-300;
+        let mut b = x + 1.0 - a;
+        let mut c = 1.0 / tiny;
+        let mut d = 1.0 / b;
+        let mut h = d;
+        for i in 1..1000 {
+            let an = -(i as f64) * (i as f64 - a);
+            b += 2.0;
+            d = an * d + b;
+            if d.abs() < tiny {
+                d = tiny;
+            }
+            c = b + an / c;
+            if c.abs() < tiny {
+                c = tiny;
+            }
+            d = 1.0 / d;
+            let delta = d * c;
+            h *= delta;
+            if (delta - 1.0).abs() < 1e-15 {
+                break;
+            }
+        }
+        (h * ln_prefix.exp()).clamp(0.0, 1.0)
+    }
+}
+
+pub fn chi_square_survival(statistic: f64, degrees_of_freedom: f64) -> f64 {
+    regularized_gamma_q(degrees_of_freedom / 2.0, statistic / 2.0)
+}
+
+pub fn kolmogorov_survival(lambda: f64) -> f64 {
+    if lambda < 1e-3 {
+        return 1.0;
+    }
+    let mut sum = 0.0;
+    for j in 1..=100 {
+        let j = j as f64;
+        let term = 2.0 * (-1.0f64).powf(j - 1.0) * (-2.0 * j * j * lambda * lambda).exp();
+        sum += term;
+        if term.abs() < 1e-12 {
+            break;
+        }
+    }
+    sum.clamp(0.0, 1.0)
+}
+
+pub struct SacMatrix {
+    pub input_bits: usize,
+    pub output_bits: usize,
+    pub samples: usize,
+    pub seed: u64,
+    pub kind: MessageKind,
+    // Row-major: `probabilities[i * output_bits + j]` is P(output bit j flips | input bit i flipped).
+    pub probabilities: Vec<f64>,
+}
+
+impl SacMatrix {
+    pub fn compute(
+        algorithm: &dyn HashAlgorithm,
+        message_len: usize,
+        samples: usize,
+        seed: u64,
+        kind: MessageKind,
+    ) -> SacMatrix {
+        SacMatrix::compute_with_progress(
+            algorithm,
+            message_len,
+            samples,
+            seed,
+            kind,
+            &Progress::none(),
+        )
+    }
+
+    // One step per random message; the counts are incomplete once the job
+    // was cancelled.
+    pub fn compute_with_progress(
+        algorithm: &dyn HashAlgorithm,
+        message_len: usize,
+        samples: usize,
+        seed: u64,
+        kind: MessageKind,
+        progress: &Progress,
+    ) -> SacMatrix {
+        progress.stage("SAC messages", samples);
+        let input_bits = message_len * 8;
+        let output_bits = algorithm.output_bits();
+
+        let counts = (0..samples)
+            .into_par_iter()
+            .fold(
+                || vec![0u32; input_bits * output_bits],
+                |mut counts, sample| {
+                    if !progress.step() {
+                        return counts;
+                    }
+                    let mut message = kind.message(message_len, seed, sample);
+                    let original = algorithm.hash(&message);
+
+                    for i in 0..input_bits {
+                        manipulations::flip_bit_in_bytes(&mut message, i);
+                        let diff: Vec<u8> = algorithm
+                            .hash(&message)
+                            .iter()
+                            .zip(&original)
+                            .map(|(a, b)| a ^ b)
+                            .collect();
+                        manipulations::flip_bit_in_bytes(&mut message, i);
+
+                        let row = &mut counts[i * output_bits..(i + 1) * output_bits];
+                        for (j, count) in row.iter_mut().enumerate() {
+                            if manipulations::digest_bit(&diff, j) {
+                                *count += 1;
+                            }
+                        }
+                    }
+                    counts
+                },
+            )
+            .reduce(
+                || vec![0u32; input_bits * output_bits],
+                |mut a, b| {
+                    a.iter_mut().zip(b).for_each(|(x, y)| *x += y);
+                    a
+                },
+            );
+
+        let probabilities = counts
+            .into_iter()
+            .map(|c| {
+                if samples == 0 {
+                    0.0
+                } else {
+                    c as f64 / samples as f64
+                }
+            })
+            .collect();
+
+        SacMatrix {
+            input_bits,
+            output_bits,
+            samples,
+            seed,
+            kind,
+            probabilities,
+        }
+    }
+
+    pub fn probability(&self, input_bit: usize, output_bit: usize) -> f64 {
+        self.probabilities[input_bit * self.output_bits + output_bit]
+    }
+
+    pub fn deviation(&self, input_bit: usize, output_bit: usize) -> f64 {
+        self.probability(input_bit, output_bit) - 0.5
+    }
+
+    pub fn mean_probability(&self) -> f64 {
+        if self.probabilities.is_empty() {
+            return 0.0;
+        }
+        self.probabilities.iter().sum::<f64>() / self.probabilities.len() as f64
+    }
+
+    pub fn mean_absolute_deviation(&self) -> f64 {
+        if self.probabilities.is_empty() {
+            return 0.0;
+        }
+        self.probabilities
+            .iter()
+            .map(|p| (p - 0.5).abs())
+            .sum::<f64>()
+            / self.probabilities.len() as f64
+    }
+
+    // Returns (input bit, output bit, deviation) of the cell farthest from 0.5.
+    pub fn max_deviation(&self) -> Option<(usize, usize, f64)> {
+        self.probabilities
+            .iter()
+            .enumerate()
+            .map(|(k, p)| (k / self.output_bits, k % self.output_bits, p - 0.5))
+            .max_by(|a, b| a.2.abs().total_cmp(&b.2.abs()))
+    }
+
+    // Standard error of a single cell for an ideal function.
+    pub fn expected_noise(&self) -> f64 {
+        if self.samples == 0 {
+            return 0.0;
+        }
+        0.5 / (self.samples as f64).sqrt()
+    }
+}
+
+pub struct BicMatrix {
+    pub input_bits: usize,
+    pub output_bits: usize,
+    pub samples: usize,
+    pub seed: u64,
+    pub kind: MessageKind,
+    // Symmetric `output_bits x output_bits`; entry (j, k) is the correlation of largest
+    // magnitude between changes of output bits j and k over all input bit flips.
+    pub correlations: Vec<f64>,
+}
+
+impl BicMatrix {
+    pub fn compute(
+        algorithm: &dyn HashAlgorithm,
+        message_len: usize,
+        samples: usize,
+        seed: u64,
+        kind: MessageKind,
+    ) -> BicMatrix {
+        BicMatrix::compute_with_progress(
+            algorithm,
+            message_len,
+            samples,
+            seed,
+            kind,
+            &Progress::none(),
+        )
+    }
+
+    // One step per input bit; input bits left once the job was cancelled
+    // are skipped.
+    pub fn compute_with_progress(
+        algorithm: &dyn HashAlgorithm,
+        message_len: usize,
+        samples: usize,
+        seed: u64,
+        kind: MessageKind,
+        progress: &Progress,
+    ) -> BicMatrix {
+        progress.stage("BIC input bits", message_len * 8);
+        let input_bits = message_len * 8;
+        let output_bits = algorithm.output_bits();
+        let words = samples.div_ceil(64);
+
+        let messages: Vec<Vec<u8>> = (0..samples)
+            .map(|sample| kind.message(message_len, seed, sample))
+            .collect();
+        let originals: Vec<Vec<u8>> = messages.par_iter().map(|m| algorithm.hash(m)).collect();
+
+        let mut correlations = (0..input_bits)
+            .into_par_iter()
+            .filter(|_| progress.step())
+            .map(|i| {
+                // Change indicators of each output bit, bit-sliced across samples.
+                let mut columns = vec![0u64; output_bits * words];
+                let mut message = vec![0u8; message_len];
+                for (sample, (original_message, original)) in
+                    messages.iter().zip(&originals).enumerate()
+                {
+                    message.copy_from_slice(original_message);
+                    manipulations::flip_bit_in_bytes(&mut message, i);
+                    let diff: Vec<u8> = algorithm
+                        .hash(&message)
+                        .iter()
+                        .zip(original)
+                        .map(|(a, b)| a ^ b)
+                        .collect();
+                    for j in 0..output_bits {
+                        if manipulations::digest_bit(&diff, j) {
+                            columns[j * words + sample / 64] |= 1 << (sample % 64);
+                        }
+                    }
+                }
+
+                let column = |j: usize| &columns[j * words..(j + 1) * words];
+                let ones: Vec<u32> = (0..output_bits)
+                    .map(|j| column(j).iter().map(|w| w.count_ones()).sum())
+                    .collect();
+
+                let mut matrix = vec![0.0; output_bits * output_bits];
+                for j in 0..output_bits {
+                    for k in j + 1..output_bits {
+                        let both: u32 = column(j)
+                            .iter()
+                            .zip(column(k))
+                            .map(|(a, b)| (a & b).count_ones())
+                            .sum();
+                        let c = phi_coefficient(samples, ones[j], ones[k], both);
+                        matrix[j * output_bits + k] = c;
+                        matrix[k * output_bits + j] = c;
+                    }
+                }
+                matrix
+            })
+            .reduce(
+                || vec![0.0; output_bits * output_bits],
+                |mut a, b| {
+                    for (x, y) in a.iter_mut().zip(b) {
+                        if y.abs() > x.abs() || (y.abs() == x.abs() && y > *x) {
+                            *x = y;
+                        }
+                    }
+                    a
+                },
+            );
+
+        for j in 0..output_bits {
+            correlations[j * output_bits + j] = 1.0;
+        }
+
+        BicMatrix {
+            input_bits,
+            output_bits,
+            samples,
+            seed,
+            kind,
+            correlations,
+        }
+    }
+
+    pub fn correlation(&self, j: usize, k: usize) -> f64 {
+        self.correlations[j * self.output_bits + k]
+    }
+
+    fn off_diagonal(&self) -> impl Iterator<Item = (usize, usize, f64)> + '_ {
+        (0..self.output_bits)
+            .flat_map(move |j| (j + 1..self.output_bits).map(move |k| (j, k)))
+            .map(|(j, k)| (j, k, self.correlation(j, k)))
+    }
+
+    // Returns (output bit j, output bit k, correlation) of the most correlated pair.
+    pub fn max_abs_correlation(&self) -> Option<(usize, usize, f64)> {
+        if self.input_bits == 0 {
+            return None;
+        }
+        self.off_diagonal()
+            .max_by(|a, b| a.2.abs().total_cmp(&b.2.abs()))
+    }
+
+    pub fn mean_abs_correlation(&self) -> f64 {
+        let pairs = self.output_bits * self.output_bits.saturating_sub(1) / 2;
+        if self.input_bits == 0 || pairs == 0 {
+            return 0.0;
+        }
+        self.off_diagonal().map(|(_, _, c)| c.abs()).sum::<f64>() / pairs as f64
+    }
+
+    // Standard error of a single correlation for an ideal function.
+    pub fn expected_noise(&self) -> f64 {
+        if self.samples == 0 {
+            return 0.0;
+        }
+        1.0 / (self.samples as f64).sqrt()
+    }
+}
+
+// How often each output bit changed over a series of flips, in digest display order.
+#[derive(Clone, Debug)]
+pub struct OutputBitCounts {
+    pub trials: usize,
+    pub changes: Vec<u64>,
+}
+
+impl OutputBitCounts {
+    pub fn new(output_bits: usize) -> OutputBitCounts {
+        OutputBitCounts {
+            trials: 0,
+            changes: vec![0; output_bits],
+        }
+    }
+
+    pub fn add(&mut self, original: &[u8], flipped: &[u8]) {
+        for (byte, (a, b)) in original.iter().zip(flipped).enumerate() {
+            let mut diff = a ^ b;
+            while diff != 0 {
+                let bit = diff.leading_zeros() as usize;
+                self.changes[byte * 8 + bit] += 1;
+                diff &= !(0x80 >> bit);
+            }
+        }
+        self.trials += 1;
+    }
+
+    pub fn merge(mut self, other: OutputBitCounts) -> OutputBitCounts {
+        self.trials += other.trials;
+        self.changes
+            .iter_mut()
+            .zip(other.changes)
+            .for_each(|(a, b)| *a += b);
+        self
+    }
+
+    pub fn rate(&self, bit: usize) -> f64 {
+        if self.trials == 0 {
+            return 0.0;
+        }
+        self.changes[bit] as f64 / self.trials as f64
+    }
+
+    // Wilson score interval for the change rate of one output bit.
+    pub fn interval(&self, bit: usize, z: f64) -> (f64, f64) {
+        wilson_interval(self.changes[bit], self.trials, z)
+    }
+}
+
+pub fn wilson_interval(successes: u64, trials: usize, z: f64) -> (f64, f64) {
+    if trials == 0 {
+        return (0.0, 1.0);
+    }
+    let n = trials as f64;
+    let p = successes as f64 / n;
+    let z2 = z * z;
+    let center = (p + z2 / (2.0 * n)) / (1.0 + z2 / n);
+    let half = z / (1.0 + z2 / n) * (p * (1.0 - p) / n + z2 / (4.0 * n * n)).sqrt();
+    ((center - half).max(0.0), (center + half).min(1.0))
+}
+
+// Inverse of the standard normal CDF, Acklam's rational approximation
+// (relative error below 1.2e-9).
+pub fn normal_quantile(p: f64) -> f64 {
+    const A: [f64; 6] = [
+        -3.969683028665376e1,
+        2.209460984245205e2,
+        -2.759285104469687e2,
+        1.38357751867269e2,
+        -3.066479806614716e1,
+        2.506628277459239,
+    ];
+    const B: [f64; 5] = [
+        -5.447609879822406e1,
+        1.615858368580409e2,
+        -1.556989798598866e2,
+        6.680131188771972e1,
+        -1.328068155288572e1,
+    ];
+    const C: [f64; 6] = [
+        -7.784894002430293e-3,
+        -3.223964580411365e-1,
+        -2.400758277161838,
+        -2.549732539343734,
+        4.374664141464968,
+        2.938163982698783,
+    ];
+    const D: [f64; 4] = [
+        7.784695709041462e-3,
+        3.224671290700398e-1,
+        2.445134137142996,
+        3.754408661907416,
+    ];
+    const LOW: f64 = 0.02425;
+
+    if p <= 0.0 {
+        return f64::NEG_INFINITY;
+    }
+    if p >= 1.0 {
+        return f64::INFINITY;
+    }
+    if p < LOW {
+        let q = (-2.0 * p.ln()).sqrt();
+        (((((C[0] * q + C[1]) * q + C[2]) * q + C[3]) * q + C[4]) * q + C[5])
+            / ((((D[0] * q + D[1]) * q + D[2]) * q + D[3]) * q + 1.0)
+    } else if p <= 1.0 - LOW {
+        let q = p - 0.5;
+        let r = q * q;
+        (((((A[0] * r + A[1]) * r + A[2]) * r + A[3]) * r + A[4]) * r + A[5]) * q
+            / (((((B[0] * r + B[1]) * r + B[2]) * r + B[3]) * r + B[4]) * r + 1.0)
+    } else {
+        -normal_quantile(1.0 - p)
+    }
+}
+
+pub struct PairedTest {
+    pub pairs: usize,
+    // Pairs with a non-zero difference; ties at zero carry no information.
+    pub nonzero: usize,
+    pub mean_difference: f64,
+    // W+, the rank sum of the positive differences.
+    pub statistic: f64,
+    pub z: f64,
+    pub p_value: f64,
+}
+
+// Two-sided Wilcoxon signed-rank test of `a - b` with average ranks for ties,
+// the tie-corrected variance and a continuity correction.
+pub fn wilcoxon_signed_rank(a: &[f64], b: &[f64]) -> Option<PairedTest> {
+    if a.len() != b.len() || a.is_empty() {
+        return None;
+    }
+    let differences: Vec<f64> = a.iter().zip(b).map(|(x, y)| x - y).collect();
+    let mean_difference = differences.iter().sum::<f64>() / differences.len() as f64;
+    let mut nonzero: Vec<f64> = differences.into_iter().filter(|d| *d != 0.0).collect();
+    nonzero.sort_by(|x, y| x.abs().total_cmp(&y.abs()));
+
+    let n = nonzero.len() as f64;
+    let mut statistic = 0.0;
+    let mut tie_correction = 0.0;
+    let mut start = 0;
+    while start < nonzero.len() {
+        let mut end = start + 1;
+        while end < nonzero.len() && nonzero[end].abs() == nonzero[start].abs() {
+            end += 1;
+        }
+        // Ranks start + 1 ..= end share their average.
+        let rank = (start + end + 1) as f64 / 2.0;
+        statistic += rank * nonzero[start..end].iter().filter(|d| **d > 0.0).count() as f64;
+        let t = (end - start) as f64;
+        tie_correction += t * t * t - t;
+        start = end;
+    }
+
+    let mean = n * (n + 1.0) / 4.0;
+    let variance = n * (n + 1.0) * (2.0 * n + 1.0) / 24.0 - tie_correction / 48.0;
+    let (z, p_value) = if variance > 0.0 {
+        let shifted = (statistic - mean).abs() - 0.5;
+        let z = shifted.max(0.0) / variance.sqrt() * (statistic - mean).signum();
+        // Two-sided normal tail: erfc(|z| / sqrt 2) = Q(1/2, z^2 / 2).
+        (z, regularized_gamma_q(0.5, z * z / 2.0))
     } else {
-        (diff_count as f64 / chars1.len() as f64) * 100.0
+        (0.0, 1.0)
     };
 
-    (percent, older, newer)
+    Some(PairedTest {
+        pairs: a.len(),
+        nonzero: nonzero.len(),
+        mean_difference,
+        statistic,
+        z,
+        p_value,
+    })
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct MeanInterval {
+    pub mean: f64,
+    pub low: f64,
+    pub high: f64,
+}
+
+impl MeanInterval {
+    pub fn width(&self) -> f64 {
+        self.high - self.low
+    }
+}
+
+// Normal-approximation interval for the mean of a sample drawn without
+// replacement from `population` values; shrinks to the mean once the sample
+// is the whole population.
+pub fn mean_interval(values: &[f64], population: usize, confidence: f64) -> Option<MeanInterval> {
+    let summary = Summary::from_values(values)?;
+    if summary.count < 2 {
+        return None;
+    }
+    let n = summary.count as f64;
+    let sample_variance = summary.variance * n / (n - 1.0);
+    let correction = if population > 1 {
+        ((population as f64 - n) / (population as f64 - 1.0)).max(0.0)
+    } else {
+        0.0
+    };
+    let z = normal_quantile(1.0 - (1.0 - confidence) / 2.0);
+    let half = z * (sample_variance / n * correction).sqrt();
+    Some(MeanInterval {
+        mean: summary.mean,
+        low: summary.mean - half,
+        high: summary.mean + half,
+    })
+}
+
+// Pearson correlation of two binary variables; 0 when either one is constant.
+fn phi_coefficient(samples: usize, ones_a: u32, ones_b: u32, both: u32) -> f64 {
+    let n = samples as f64;
+    let (a, b, c) = (ones_a as f64, ones_b as f64, both as f64);
+    let variance = a * (n - a) * b * (n - b);
+    if variance == 0.0 {
+        return 0.0;
+    }
+    (n * c - a * b) / variance.sqrt()
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    struct Identity;
+
+    impl HashAlgorithm for Identity {
+        fn name(&self) -> &'static str {
+            "identity"
+        }
+
+        fn output_bits(&self) -> usize {
+            16
+        }
+
+        fn hash(&self, data: &[u8]) -> Vec<u8> {
+            data.to_vec()
+        }
+    }
+
     #[test]
-    fn test_percent_difference_identical_strings() {
-        let (percent, old_colored, new_colored) = percent_difference("abcdef", "abcdef");
-        assert_eq!(percent, 0.0);
-        assert_eq!(old_colored.len(), 6);
-        assert_eq!(new_colored.len(), 6);
+    fn test_sac_matrix_dimensions() {
+        let sac = SacMatrix::compute(algorithms::ALGORITHMS[0], 2, 8, 1, MessageKind::Uniform);
+        assert_eq!(sac.input_bits, 16);
+        assert_eq!(sac.output_bits, 256);
+        assert_eq!(sac.probabilities.len(), 16 * 256);
+        assert_eq!(sac.samples, 8);
+    }
 
-        for colored in &old_colored {
-            assert!(matches!(colored.color, Color::Green));
+    #[test]
+    fn test_sac_matrix_identity_is_diagonal() {
+        let sac = SacMatrix::compute(&Identity, 2, 16, 1, MessageKind::Uniform);
+        for i in 0..16 {
+            // Input bit 0 is the LSB of the last byte, i.e. output bit 15 in display order.
+            let expected = 15 - i;
+            for j in 0..16 {
+                let p = sac.probability(i, j);
+                if j == expected {
+                    assert_eq!(p, 1.0);
+                } else {
+                    assert_eq!(p, 0.0);
+                }
+            }
         }
-        for colored in &new_colored {
-            assert!(matches!(colored.color, Color::Green));
+        assert_eq!(sac.mean_absolute_deviation(), 0.5);
+    }
+
+    #[test]
+    fn test_sac_matrix_sha256_close_to_half() {
+        let sac = SacMatrix::compute(algorithms::ALGORITHMS[0], 4, 256, 7, MessageKind::Uniform);
+        assert!((sac.mean_probability() - 0.5).abs() < 0.01);
+        let (_, _, deviation) = sac.max_deviation().unwrap();
+        assert!(deviation.abs() < 6.0 * sac.expected_noise());
+    }
+
+    #[test]
+    fn test_sac_matrix_is_reproducible() {
+        let a = SacMatrix::compute(algorithms::ALGORITHMS[0], 2, 32, 99, MessageKind::Uniform);
+        let b = SacMatrix::compute(algorithms::ALGORITHMS[0], 2, 32, 99, MessageKind::Uniform);
+        assert_eq!(a.probabilities, b.probabilities);
+    }
+
+    #[test]
+    fn test_sac_matrix_empty_message() {
+        let sac = SacMatrix::compute(algorithms::ALGORITHMS[0], 0, 8, 1, MessageKind::Uniform);
+        assert!(sac.probabilities.is_empty());
+        assert!(sac.max_deviation().is_none());
+        assert_eq!(sac.mean_probability(), 0.0);
+    }
+
+    struct AndTwice;
+
+    impl HashAlgorithm for AndTwice {
+        fn name(&self) -> &'static str {
+            "and-twice"
+        }
+
+        fn output_bits(&self) -> usize {
+            16
+        }
+
+        fn hash(&self, data: &[u8]) -> Vec<u8> {
+            vec![data[0] & data[1], data[0] & data[1]]
         }
     }
 
     #[test]
-    fn test_percent_difference_completely_different() {
-        let (percent, old_colored, new_colored) = percent_difference("aaaaaa", "bbbbbb");
-        assert_eq!(percent, 100.0);
-        assert_eq!(old_colored.len(), 6);
-        assert_eq!(new_colored.len(), 6);
+    fn test_phi_coefficient() {
+        assert_eq!(phi_coefficient(4, 2, 2, 2), 1.0);
+        assert_eq!(phi_coefficient(4, 2, 2, 0), -1.0);
+        assert_eq!(phi_coefficient(4, 2, 2, 1), 0.0);
+        assert_eq!(phi_coefficient(4, 4, 2, 2), 0.0);
+    }
 
-        for colored in &old_colored {
-            assert!(matches!(colored.color, Color::Red));
+    #[test]
+    fn test_bic_matrix_detects_duplicated_output_bits() {
+        let bic = BicMatrix::compute(&AndTwice, 2, 128, 3, MessageKind::Uniform);
+        assert_eq!(bic.correlations.len(), 16 * 16);
+        for j in 0..8 {
+            assert!((bic.correlation(j, j + 8) - 1.0).abs() < 1e-9);
         }
-        for colored in &new_colored {
-            assert!(matches!(colored.color, Color::Yellow));
+        let (_, _, max) = bic.max_abs_correlation().unwrap();
+        assert!((max - 1.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_bic_matrix_is_symmetric_with_unit_diagonal() {
+        let bic = BicMatrix::compute(algorithms::ALGORITHMS[0], 1, 64, 5, MessageKind::Uniform);
+        for j in 0..bic.output_bits {
+            assert_eq!(bic.correlation(j, j), 1.0);
+            for k in 0..bic.output_bits {
+                assert_eq!(bic.correlation(j, k), bic.correlation(k, j));
+            }
         }
     }
 
     #[test]
-    fn test_percent_difference_half_different() {
-        let (percent, old_colored, new_colored) = percent_difference("aaabbb", "aaaccc");
-        assert_eq!(percent, 50.0);
-        assert_eq!(old_colored.len(), 6);
-        assert_eq!(new_colored.len(), 6);
+    fn test_bic_matrix_sha256_is_weakly_correlated() {
+        let bic = BicMatrix::compute(algorithms::ALGORITHMS[0], 4, 256, 11, MessageKind::Uniform);
+        assert!(bic.mean_abs_correlation() < 3.0 * bic.expected_noise());
+        let (_, _, max) = bic.max_abs_correlation().unwrap();
+        assert!(max.abs() < 0.5);
+    }
+
+    #[test]
+    fn test_bic_matrix_empty_message() {
+        let bic = BicMatrix::compute(algorithms::ALGORITHMS[0], 0, 16, 1, MessageKind::Uniform);
+        assert!(bic.max_abs_correlation().is_none());
+        assert_eq!(bic.mean_abs_correlation(), 0.0);
+    }
+
+    #[test]
+    fn test_summary_empty() {
+        assert!(Summary::from_values(&[]).is_none());
+    }
+
+    #[test]
+    fn test_summary_basic_moments() {
+        let summary = Summary::from_values(&[2.0, 4.0, 4.0, 4.0, 5.0, 5.0, 7.0, 9.0]).unwrap();
+        assert_eq!(summary.count, 8);
+        assert_eq!(summary.min, 2.0);
+        assert_eq!(summary.max, 9.0);
+        assert_eq!(summary.mean, 5.0);
+        assert_eq!(summary.variance, 4.0);
+        assert_eq!(summary.std_dev, 2.0);
+        assert_eq!(summary.median, 4.5);
+        assert!(summary.skewness > 0.0);
+    }
+
+    #[test]
+    fn test_summary_percentiles_interpolate() {
+        let values: Vec<f64> = (0..=100).map(|v| v as f64).collect();
+        let summary = Summary::from_values(&values).unwrap();
+        assert_eq!(summary.q1, 25.0);
+        assert_eq!(summary.q3, 75.0);
+        assert_eq!(summary.p1, 1.0);
+        assert_eq!(summary.p5, 5.0);
+        assert_eq!(summary.p95, 95.0);
+        assert_eq!(summary.p99, 99.0);
+        assert!(summary.skewness.abs() < 1e-12);
+        // Uniform distribution has excess kurtosis close to -1.2.
+        assert!((summary.kurtosis + 1.2).abs() < 0.01);
+    }
+
+    #[test]
+    fn test_summary_constant_values() {
+        let summary = Summary::from_values(&[3.0; 5]).unwrap();
+        assert_eq!(summary.std_dev, 0.0);
+        assert_eq!(summary.skewness, 0.0);
+        assert_eq!(summary.kurtosis, 0.0);
+        assert_eq!(summary.p99, 3.0);
+    }
+
+    #[test]
+    fn test_summary_unsorted_input() {
+        let summary = Summary::from_values(&[3.0, 1.0, 2.0]).unwrap();
+        assert_eq!(summary.min, 1.0);
+        assert_eq!(summary.median, 2.0);
+        assert_eq!(summary.max, 3.0);
+    }
+
+    #[test]
+    fn test_binomial_ideal() {
+        let binomial = Binomial::ideal(256);
+        assert_eq!(binomial.mean(), 128.0);
+        assert_eq!(binomial.variance(), 64.0);
+        assert_eq!(binomial.std_dev(), 8.0);
+    }
+
+    fn binomial_sample(samples: usize) -> Vec<usize> {
+        let mut rng = random::Rng::new(2024);
+        (0..samples)
+            .map(|_| rng.next_u64().count_ones() as usize)
+            .collect()
+    }
+
+    #[test]
+    fn test_binomial_pmf() {
+        let binomial = Binomial::ideal(4);
+        assert!((binomial.pmf(0) - 1.0 / 16.0).abs() < 1e-12);
+        assert!((binomial.pmf(2) - 6.0 / 16.0).abs() < 1e-12);
+        assert_eq!(binomial.pmf(5), 0.0);
+        let total: f64 = (0..=4).map(|k| binomial.pmf(k)).sum();
+        assert!((total - 1.0).abs() < 1e-12);
+    }
+
+    #[test]
+    fn test_ln_gamma_factorials() {
+        assert!(ln_gamma(1.0).abs() < 1e-12);
+        assert!((ln_gamma(5.0) - 24f64.ln()).abs() < 1e-10);
+        assert!((ln_gamma(0.5) - std::f64::consts::PI.sqrt().ln()).abs() < 1e-10);
+    }
 
-        assert!(matches!(old_colored[0].color, Color::Green));
-        assert!(matches!(old_colored[1].color, Color::Green));
-        assert!(matches!(old_colored[2].color, Color::Green));
-        assert!(matches!(old_colored[3].color, Color::Red));
-        assert!(matches!(old_colored[4].color, Color::Red));
-        assert!(matches!(old_colored[5].color, Color::Red));
+    #[test]
+    fn test_chi_square_survival_known_values() {
+        // Critical values of the chi-square distribution at alpha = 0.05.
+        assert!((chi_square_survival(3.841, 1.0) - 0.05).abs() < 1e-3);
+        assert!((chi_square_survival(18.307, 10.0) - 0.05).abs() < 1e-3);
+        assert_eq!(chi_square_survival(0.0, 3.0), 1.0);
     }
 
     #[test]
-    fn test_percent_difference_different_lengths() {
-        let (percent, old_colored, new_colored) = percent_difference("abc", "abcdef");
-        assert_eq!(percent, 0.0);
-        assert_eq!(old_colored.len(), 0);
-        assert_eq!(new_colored.len(), 0);
+    fn test_kolmogorov_survival_known_values() {
+        assert!((kolmogorov_survival(1.358) - 0.05).abs() < 1e-3);
+        assert_eq!(kolmogorov_survival(0.0), 1.0);
+        assert!(kolmogorov_survival(3.0) < 1e-6);
     }
 
     #[test]
-    fn test_percent_difference_empty_strings() {
-        let (percent, old_colored, new_colored) = percent_difference("", "");
-        assert_eq!(percent, 0.0);
-        assert_eq!(old_colored.len(), 0);
-        assert_eq!(new_colored.len(), 0);
+    fn test_goodness_of_fit_accepts_binomial_sample() {
+        let reference = Binomial::ideal(64);
+        let distances = binomial_sample(2000);
+
+        let chi = chi_square_test(&distances, &reference).unwrap();
+        assert!(chi.passes(0.01));
+        assert!(chi.degrees_of_freedom.unwrap() > 5);
+
+        let ks = ks_test(&distances, &reference).unwrap();
+        assert!(ks.passes(0.01));
     }
 
     #[test]
-    fn test_percent_difference_one_char_different() {
-        let (percent, old_colored, new_colored) = percent_difference("abcd", "abXd");
-        assert!((percent - 25.0).abs() < 0.01);
-        assert_eq!(old_colored.len(), 4);
-        assert_eq!(new_colored.len(), 4);
+    fn test_goodness_of_fit_rejects_constant_distances() {
+        let reference = Binomial::ideal(64);
+        let distances = vec![32; 500];
 
-        assert!(matches!(old_colored[2].color, Color::Red));
-        assert_eq!(old_colored[2].text, "c");
-        assert!(matches!(new_colored[2].color, Color::Yellow));
-        assert_eq!(new_colored[2].text, "X");
+        assert!(
+            !chi_square_test(&distances, &reference)
+                .unwrap()
+                .passes(0.05)
+        );
+        assert!(!ks_test(&distances, &reference).unwrap().passes(0.05));
     }
 
     #[test]
-    fn test_percent_difference_unicode() {
-        let (percent, old_colored, new_colored) = percent_difference("🔥🔥", "🔥💧");
-        assert_eq!(percent, 50.0);
-        assert_eq!(old_colored.len(), 2);
-        assert_eq!(new_colored.len(), 2);
+    fn test_goodness_of_fit_without_data() {
+        let reference = Binomial::ideal(64);
+        assert!(chi_square_test(&[], &reference).is_none());
+        assert!(ks_test(&[], &reference).is_none());
     }
 
     #[test]
-    fn test_percent_difference_text_content() {
-        let (percent, old_colored, new_colored) = percent_difference("hello", "world");
-        assert_eq!(percent, 80.0);
+    fn test_normal_quantile() {
+        assert!((normal_quantile(0.975) - 1.959964).abs() < 1e-6);
+        assert!((normal_quantile(0.5)).abs() < 1e-12);
+        assert!((normal_quantile(0.001) + 3.090232).abs() < 1e-6);
+        assert_eq!(normal_quantile(0.0), f64::NEG_INFINITY);
+    }
 
-        assert_eq!(old_colored[0].text, "h");
-        assert_eq!(old_colored[1].text, "e");
-        assert_eq!(old_colored[2].text, "l");
-        assert_eq!(old_colored[3].text, "l");
-        assert_eq!(old_colored[4].text, "o");
+    #[test]
+    fn test_wilson_interval() {
+        let (low, high) = wilson_interval(50, 100, 1.96);
+        assert!((low - 0.4038).abs() < 1e-3);
+        assert!((high - 0.5962).abs() < 1e-3);
+
+        let (low, high) = wilson_interval(0, 10, 1.96);
+        assert_eq!(low, 0.0);
+        assert!(high > 0.2 && high < 0.35);
+        assert_eq!(wilson_interval(0, 0, 1.96), (0.0, 1.0));
+    }
+
+    #[test]
+    fn test_wilcoxon_signed_rank() {
+        // Differences 1, -2, 3, 4, 5 (and one zero): W+ = 1 + 3 + 4 + 5 = 13,
+        // mean 7.5, variance 13.75.
+        let a = [11.0, 8.0, 13.0, 14.0, 15.0, 10.0];
+        let b = [10.0; 6];
+        let test = wilcoxon_signed_rank(&a, &b).unwrap();
+        assert_eq!(test.pairs, 6);
+        assert_eq!(test.nonzero, 5);
+        assert_eq!(test.statistic, 13.0);
+        assert!((test.z - 5.0 / 13.75f64.sqrt()).abs() < 1e-12);
+        assert!((test.p_value - 0.1775).abs() < 1e-3);
+
+        // Tied magnitudes share ranks 1.5 and 1.5.
+        let tied = wilcoxon_signed_rank(&[1.0, -1.0], &[0.0, 0.0]).unwrap();
+        assert_eq!(tied.statistic, 1.5);
+
+        let shifted: Vec<f64> = (0..200).map(|i| (i % 17) as f64 + 2.0).collect();
+        let base: Vec<f64> = (0..200).map(|i| (i % 17) as f64).collect();
+        assert!(wilcoxon_signed_rank(&shifted, &base).unwrap().p_value < 1e-10);
+
+        let same = wilcoxon_signed_rank(&base, &base).unwrap();
+        assert_eq!((same.nonzero, same.p_value), (0, 1.0));
+        assert!(wilcoxon_signed_rank(&[1.0], &[1.0, 2.0]).is_none());
+    }
+
+    #[test]
+    fn test_mean_interval() {
+        // Sample std 1.5811 over 5 values: half width 1.96 * 1.5811 / sqrt(5).
+        let values = [1.0, 2.0, 3.0, 4.0, 5.0];
+        let interval = mean_interval(&values, usize::MAX, 0.95).unwrap();
+        assert_eq!(interval.mean, 3.0);
+        assert!((interval.width() / 2.0 - 1.3859).abs() < 1e-3);
+
+        let corrected = mean_interval(&values, 9, 0.95).unwrap();
+        assert!((corrected.width() / interval.width() - 0.5f64.sqrt()).abs() < 1e-6);
+
+        let census = mean_interval(&values, 5, 0.95).unwrap();
+        assert_eq!(census.width(), 0.0);
+        assert!(mean_interval(&[1.0], 10, 0.95).is_none());
+    }
+
+    #[test]
+    fn test_output_bit_counts() {
+        let mut counts = OutputBitCounts::new(16);
+        counts.add(&[0x00, 0x00], &[0x80, 0x01]);
+        counts.add(&[0x00, 0x00], &[0x80, 0x00]);
+        assert_eq!(counts.trials, 2);
+        assert_eq!(counts.rate(0), 1.0);
+        assert_eq!(counts.rate(15), 0.5);
+        assert_eq!(counts.rate(1), 0.0);
 
-        assert_eq!(new_colored[0].text, "w");
-        assert_eq!(new_colored[1].text, "o");
-        assert_eq!(new_colored[2].text, "r");
-        assert_eq!(new_colored[3].text, "l");
-        assert_eq!(new_colored[4].text, "d");
+        let merged = counts.clone().merge(counts);
+        assert_eq!(merged.trials, 4);
+        assert_eq!(merged.changes[0], 4);
     }
 }
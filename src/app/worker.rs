@@ -0,0 +1,396 @@
+use crate::app::*;
+use std::sync::{
+    Arc,
+    atomic::{AtomicBool, AtomicUsize, Ordering},
+    mpsc::{self, Receiver, Sender, TryRecvError},
+};
+use std::thread;
+
+// Steps between two progress messages, so the channel isn't flooded by
+// fast hash functions.
+const REPORT_EVERY: usize = 16;
+
+pub enum Update {
+    Stage { name: &'static str, total: usize },
+    Progress(usize),
+    // The worker's copy of the app, holding the messages to show.
+    Finished(Box<App>),
+    Cancelled,
+}
+
+// Handed to a running analysis. Without a channel nothing is reported and
+// the analysis can't be cancelled.
+pub struct Progress {
+    sender: Option<Sender<Update>>,
+    cancelled: Arc<AtomicBool>,
+    done: AtomicUsize,
+}
+
+impl Progress {
+    pub fn none() -> Progress {
+        Progress {
+            sender: None,
+            cancelled: Arc::new(AtomicBool::new(false)),
+            done: AtomicUsize::new(0),
+        }
+    }
+
+    fn send(&self, update: Update) {
+        if let Some(sender) = &self.sender {
+            // The receiver is gone once the job was cancelled or replaced.
+            let _ = sender.send(update);
+        }
+    }
+
+    pub fn stage(&self, name: &'static str, total: usize) {
+        self.done.store(0, Ordering::Relaxed);
+        self.send(Update::Stage { name, total });
+    }
+
+    // Counts one unit of work; false once the job was cancelled.
+    pub fn step(&self) -> bool {
+        if self.is_cancelled() {
+            return false;
+        }
+        let done = self.done.fetch_add(1, Ordering::Relaxed) + 1;
+        if done.is_multiple_of(REPORT_EVERY) {
+            self.send(Update::Progress(done));
+        }
+        true
+    }
+
+    pub fn is_cancelled(&self) -> bool {
+        self.cancelled.load(Ordering::Relaxed)
+    }
+}
+
+pub struct Job {
+    receiver: Receiver<Update>,
+    cancelled: Arc<AtomicBool>,
+    pub stage: &'static str,
+    pub done: usize,
+    pub total: usize,
+}
+
+impl Job {
+    pub fn ratio(&self) -> f64 {
+        if self.total == 0 {
+            return 0.0;
+        }
+        (self.done as f64 / self.total as f64).min(1.0)
+    }
+
+    pub fn label(&self) -> String {
+        if self.total == 0 {
+            return format!("{}...", self.stage);
+        }
+        format!(
+            "{}: {} / {}",
+            self.stage,
+            self.done.min(self.total),
+            self.total
+        )
+    }
+
+    fn cancel(&self) {
+        self.cancelled.store(true, Ordering::Relaxed);
+    }
+}
+
+impl App {
+    pub fn job_running(&self) -> bool {
+        self.job.is_some()
+    }
+
+    // Runs the analysis of the current mode on a copy of the app in a worker
+    // thread; `poll_job` moves its output over once it is done.
+    pub(crate) fn start_job(&mut self) {
+        let (sender, receiver) = mpsc::channel();
+        let cancelled = Arc::new(AtomicBool::new(false));
+        let mut worker = App {
+            current_mode: self.current_mode,
+            original_text: self.original_text.clone(),
+            input_source: self.input_source,
+            input_bytes: self.input_bytes.clone(),
+            algorithm_index: self.algorithm_index,
+            significance_level: self.significance_level,
+            sampling: self.sampling,
+            seed: self.seed,
+            message_kind: self.message_kind,
+            compared: self.compared.clone(),
+            progress: Progress {
+                sender: Some(sender.clone()),
+                cancelled: cancelled.clone(),
+                done: AtomicUsize::new(0),
+            },
+            ..App::new()
+        };
+
+        thread::spawn(move || {
+            worker.process_text_analysis();
+            let update = if worker.progress.is_cancelled() {
+                Update::Cancelled
+            } else {
+                worker.progress = Progress::none();
+                Update::Finished(Box::new(worker))
+            };
+            let _ = sender.send(update);
+        });
+
+        self.job = Some(Job {
+            receiver,
+            cancelled,
+            stage: "Working",
+            done: 0,
+            total: 0,
+        });
+    }
+
+    pub fn poll_job(&mut self) {
+        let Some(job) = &mut self.job else {
+            return;
+        };
+        loop {
+            match job.receiver.try_recv() {
+                Ok(Update::Stage { name, total }) => {
+                    job.stage = name;
+                    job.total = total;
+                    job.done = 0;
+                }
+                Ok(Update::Progress(done)) => job.done = done,
+                Ok(Update::Finished(mut worker)) => {
+                    self.job = None;
+                    self.comparison = worker.comparison.take();
+                    self.histogram = worker.histogram.take();
+                    self.messages.extend(worker.messages);
+                    self.colored_messages.extend(worker.colored_messages);
+                    self.last_export = worker.last_export;
+                    self.scroll_to_bottom();
+                    return;
+                }
+                Ok(Update::Cancelled) => {
+                    self.job = None;
+                    return;
+                }
+                Err(TryRecvError::Empty) => return,
+                Err(TryRecvError::Disconnected) => {
+                    self.job = None;
+                    self.messages
+                        .push("Error: the analysis stopped unexpectedly".to_string());
+                    self.scroll_to_bottom();
+                    return;
+                }
+            }
+        }
+    }
+
+    // The worker stops at its next step; whatever it still sends is dropped
+    // along with the receiver.
+    pub fn cancel_job(&mut self) {
+        if let Some(job) = self.job.take() {
+            job.cancel();
+            self.messages.push("Analysis cancelled".to_string());
+            self.messages.push("Press Enter to continue...".to_string());
+            self.scroll_to_bottom();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use random::MessageKind;
+    use sac::MAX_MATRIX_MESSAGE_LEN;
+    use statistics::{BicMatrix, SacMatrix};
+    use std::time::{Duration, Instant};
+
+    fn wait(app: &mut App) {
+        let start = Instant::now();
+        while app.job_running() {
+            assert!(start.elapsed() < Duration::from_secs(30), "job timed out");
+            app.poll_job();
+            thread::sleep(Duration::from_millis(5));
+        }
+    }
+
+    #[test]
+    fn test_background_results_match_synchronous_run() {
+        let mut sync = App::new();
+        sync.current_mode = Some(SandboxMode::Automatic);
+        sync.original_text = "avalanche".to_string();
+        sync.process_automatic();
+
+        let mut app = App::new();
+        app.current_mode = Some(SandboxMode::Automatic);
+        app.original_text = "avalanche".to_string();
+        app.start_job();
+        assert!(app.job_running());
+        wait(&mut app);
+
+        let strip_time = |messages: &[String]| -> Vec<String> {
+            messages
+                .iter()
+                .filter(|m| !m.starts_with("Computation time"))
+                .cloned()
+                .collect()
+        };
+        assert_eq!(strip_time(&app.messages), strip_time(&sync.messages));
+        assert_eq!(app.colored_messages.len(), sync.colored_messages.len());
+        assert!(app.last_export.is_some());
+    }
+
+    #[test]
+    fn test_submit_input_runs_in_background() {
+        let mut app = App::new();
+        app.run_in_background = true;
+        app.switch_to_sac();
+        app.input_buffer = "ab".to_string();
+        app.submit_input();
+
+        assert!(app.job_running());
+        assert!(matches!(app.input_state, Some(InputState::ShowingResult)));
+        wait(&mut app);
+        assert!(
+            app.messages
+                .iter()
+                .any(|m| m == "=== Strict Avalanche Criterion ===")
+        );
+    }
+
+    #[test]
+    fn test_cancel_stops_the_worker() {
+        let mut app = App::new();
+        app.current_mode = Some(SandboxMode::Automatic);
+        app.input_bytes = Some(vec![0u8; 4 << 20]);
+        app.start_job();
+        app.cancel_job();
+
+        assert!(!app.job_running());
+        assert!(app.messages.iter().any(|m| m == "Analysis cancelled"));
+        app.poll_job();
+        assert!(!app.messages.iter().any(|m| m.contains("=== Summary ===")));
+    }
+
+    #[test]
+    fn test_cancel_stops_a_bic_worker() {
+        let mut app = App::new();
+        app.current_mode = Some(SandboxMode::Bic);
+        app.input_bytes = Some(vec![0u8; MAX_MATRIX_MESSAGE_LEN]);
+        app.start_job();
+        app.cancel_job();
+
+        assert!(!app.job_running());
+        app.poll_job();
+        assert!(
+            !app.messages
+                .iter()
+                .any(|m| m == "=== Bit Independence Criterion ===")
+        );
+    }
+
+    #[test]
+    fn test_cancelled_progress_stops_matrix_and_round_runs() {
+        let cancelled = || Progress {
+            sender: None,
+            cancelled: Arc::new(AtomicBool::new(true)),
+            done: AtomicUsize::new(0),
+        };
+        let sha256 = algorithms::find("SHA-256").unwrap();
+        let start = Instant::now();
+
+        // Minutes of work each when not cancelled.
+        let sac = SacMatrix::compute_with_progress(
+            sha256,
+            MAX_MATRIX_MESSAGE_LEN,
+            sac::SAC_SAMPLES,
+            1,
+            MessageKind::Uniform,
+            &cancelled(),
+        );
+        assert!(sac.probabilities.iter().all(|&p| p == 0.0));
+        let bic = BicMatrix::compute_with_progress(
+            sha256,
+            MAX_MATRIX_MESSAGE_LEN,
+            bic::BIC_SAMPLES,
+            1,
+            MessageKind::Uniform,
+            &cancelled(),
+        );
+        assert_eq!(bic.mean_abs_correlation(), 0.0);
+        assert!(rounds::round_profile_with_progress(&[0u8; 1 << 16], &cancelled()).is_empty());
+        let linearity = linearity::detect_with_progress(
+            sha256,
+            &vec![0u8; 64 << 20],
+            linearity::LINEARITY_MESSAGES,
+            1,
+            &cancelled(),
+        );
+        assert!(!linearity.is_linear());
+
+        assert!(start.elapsed() < Duration::from_secs(10));
+    }
+
+    #[test]
+    fn test_cancelled_automatic_run_leaves_no_report() {
+        let mut app = App::new();
+        app.switch_to_automatic();
+        app.original_text = "abc".to_string();
+        app.progress = Progress {
+            sender: None,
+            cancelled: Arc::new(AtomicBool::new(true)),
+            done: AtomicUsize::new(0),
+        };
+        app.process_automatic();
+        assert_eq!(app.messages, vec!["Enter string to hash:"]);
+        assert!(app.last_export.is_none());
+        assert!(app.histogram.is_none());
+    }
+
+    #[test]
+    fn test_progress_reports_steps_until_cancelled() {
+        let (sender, receiver) = mpsc::channel();
+        let progress = Progress {
+            sender: Some(sender),
+            cancelled: Arc::new(AtomicBool::new(false)),
+            done: AtomicUsize::new(0),
+        };
+        progress.stage("Flipping bits", 40);
+        for _ in 0..40 {
+            assert!(progress.step());
+        }
+        let updates: Vec<Update> = receiver.try_iter().collect();
+        assert!(matches!(
+            updates[0],
+            Update::Stage {
+                name: "Flipping bits",
+                total: 40
+            }
+        ));
+        assert!(matches!(updates[1], Update::Progress(16)));
+        assert!(matches!(updates[2], Update::Progress(32)));
+
+        progress.cancelled.store(true, Ordering::Relaxed);
+        assert!(!progress.step());
+        assert!(Progress::none().step());
+    }
+
+    #[test]
+    fn test_job_label_and_ratio() {
+        let (_, receiver) = mpsc::channel();
+        let mut job = Job {
+            receiver,
+            cancelled: Arc::new(AtomicBool::new(false)),
+            stage: "Working",
+            done: 0,
+            total: 0,
+        };
+        assert_eq!(job.label(), "Working...");
+        assert_eq!(job.ratio(), 0.0);
+
+        job.stage = "Weight sweep";
+        job.total = 64;
+        job.done = 16;
+        assert_eq!(job.label(), "Weight sweep: 16 / 64");
+        assert_eq!(job.ratio(), 0.25);
+    }
+}
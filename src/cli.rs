@@ -0,0 +1,1152 @@
+use avalanche_effect::app::{
+    ColoredText,
+    algorithms::{self, ALGORITHMS, HashAlgorithm},
+    automatic, bias, compare,
+    export::{ExportFormat, RunExport},
+    flips::{self, FlipSpec},
+    input::Encoding,
+    linearity, manipulations, manual,
+    modes::{self, Direction, Mode},
+    random::{self, MessageKind},
+    rounds,
+    sac::{self, MAX_MATRIX_MESSAGE_LEN, SAC_SAMPLES},
+    sampling::{self, SamplingPlan, Strata, Target},
+    statistics::SacMatrix,
+    worker::Progress,
+};
+use clap::{Args, Parser, Subcommand, ValueEnum};
+use colored::Colorize;
+use ratatui::style::Color;
+use std::{
+    error::Error,
+    fs,
+    io::{self, Read, Write},
+    path::PathBuf,
+};
+
+#[derive(Parser)]
+#[command(version, about = "Demonstrates the avalanche effect in hash functions")]
+pub struct Cli {
+    #[command(subcommand)]
+    pub command: Option<Command>,
+
+    /// Disable colored output
+    #[arg(long, global = true)]
+    pub no_color: bool,
+}
+
+#[derive(Subcommand)]
+pub enum Command {
+    /// Flip every input bit in turn and summarize the avalanche effect
+    Analyze {
+        #[command(flatten)]
+        input: InputArgs,
+        #[command(flatten)]
+        hash: HashArgs,
+        #[command(flatten)]
+        seed: SeedArgs,
+        /// Significance level for the goodness-of-fit tests
+        #[arg(long, default_value_t = 0.05)]
+        alpha: f64,
+        /// Flip this many random bit positions instead of every bit
+        #[arg(long, conflicts_with = "exhaustive")]
+        sample: Option<usize>,
+        /// Sample until the 95% confidence interval of the mean avalanche is
+        /// at most this many percentage points wide
+        #[arg(long, conflicts_with_all = ["exhaustive", "sample"])]
+        ci_width: Option<f64>,
+        /// Spread the sampled positions evenly over bytes or blocks; implies sampling
+        #[arg(long, value_enum, default_value_t = Stratify::None, conflicts_with = "exhaustive")]
+        stratify: Stratify,
+        /// Bytes per block for `--stratify block`
+        #[arg(long, default_value_t = sampling::BLOCK_BYTES)]
+        block_size: usize,
+        /// Flip every bit even when the input is large enough to be sampled
+        #[arg(long)]
+        exhaustive: bool,
+        #[arg(long, value_enum, default_value_t = OutputFormat::Text)]
+        format: OutputFormat,
+    },
+    /// Flip a single input bit and compare both digests
+    Flip {
+        #[command(flatten)]
+        input: InputArgs,
+        #[command(flatten)]
+        hash: HashArgs,
+        #[command(flatten)]
+        seed: SeedArgs,
+        /// Bits to flip, 0 is the least significant bit of the last byte;
+        /// accepts lists like `0-7,12,100..120`, `byte:2`, `burst:4@10` and `random:3`
+        #[arg(short, long, alias = "bits")]
+        bit: Option<FlipSpec>,
+        #[arg(long, value_enum, default_value_t = OutputFormat::Text)]
+        format: OutputFormat,
+    },
+    /// Compute the Strict Avalanche Criterion matrix; the input length sets the message size
+    Sac {
+        #[command(flatten)]
+        input: InputArgs,
+        #[command(flatten)]
+        hash: HashArgs,
+        #[command(flatten)]
+        seed: SeedArgs,
+        /// Number of random messages
+        #[arg(long, default_value_t = SAC_SAMPLES)]
+        samples: usize,
+        /// How the random messages are generated
+        #[arg(long, value_enum, default_value_t = Generator::Uniform)]
+        generator: Generator,
+    },
+    /// Average avalanche for random input differences of 1, 2, ... bits
+    Sweep {
+        #[command(flatten)]
+        input: InputArgs,
+        #[command(flatten)]
+        hash: HashArgs,
+        #[command(flatten)]
+        seed: SeedArgs,
+        /// Largest input difference weight
+        #[arg(long, default_value_t = automatic::SWEEP_MAX_WEIGHT)]
+        max_weight: usize,
+        /// Random flips per weight
+        #[arg(long, default_value_t = automatic::SWEEP_TRIALS)]
+        trials: usize,
+    },
+    /// Check whether flipping a bit changes the output the same way for every message
+    Linearity {
+        #[command(flatten)]
+        input: InputArgs,
+        #[command(flatten)]
+        hash: HashArgs,
+        #[command(flatten)]
+        seed: SeedArgs,
+        /// Messages to compare: the input plus random ones of the same length
+        #[arg(long, default_value_t = linearity::LINEARITY_MESSAGES)]
+        messages: usize,
+    },
+    /// Flag output bits that change significantly more or less often than half the time
+    Bias {
+        #[command(flatten)]
+        input: InputArgs,
+        #[command(flatten)]
+        hash: HashArgs,
+        #[command(flatten)]
+        seed: SeedArgs,
+        /// Family-wise significance level, Bonferroni-corrected over the output bits
+        #[arg(long, default_value_t = 0.05)]
+        alpha: f64,
+        /// Random messages of the same length to flip in addition to the input
+        #[arg(long, default_value_t = bias::BIAS_RANDOM_MESSAGES)]
+        messages: usize,
+    },
+    /// Plot the average avalanche of SHA-256 reduced to 1..64 rounds
+    Rounds {
+        #[command(flatten)]
+        input: InputArgs,
+    },
+    /// Show which AES blocks change under ECB, CBC, CFB, OFB and CTR when bits are flipped
+    Modes {
+        #[command(flatten)]
+        input: InputArgs,
+        #[command(flatten)]
+        seed: SeedArgs,
+        /// Bits to flip in the plaintext and, separately, in the ciphertext
+        #[arg(short, long, alias = "bits", default_value = "0")]
+        bit: FlipSpec,
+    },
+    /// Run the automatic analysis against several hash functions on the same flips
+    Compare {
+        #[command(flatten)]
+        input: InputArgs,
+        #[command(flatten)]
+        seed: SeedArgs,
+        /// Comma-separated hash functions or cipher targets, all hash functions by default
+        #[arg(short, long, value_delimiter = ',')]
+        algorithms: Vec<String>,
+        /// Significance level for the goodness-of-fit tests
+        #[arg(long, default_value_t = 0.05)]
+        alpha: f64,
+    },
+    /// List available hash functions and cipher targets
+    List,
+    /// Start the interactive terminal UI (default)
+    Tui,
+}
+
+#[derive(Clone, Copy, ValueEnum)]
+pub enum OutputFormat {
+    /// Human-readable report
+    Text,
+    /// One JSON document with every flip record
+    Json,
+    /// One JSON object per flip record
+    Jsonl,
+    /// Comma-separated flip records with a header row
+    Csv,
+}
+
+impl OutputFormat {
+    fn export(self) -> Option<ExportFormat> {
+        match self {
+            OutputFormat::Text => None,
+            OutputFormat::Json => Some(ExportFormat::Json),
+            OutputFormat::Jsonl => Some(ExportFormat::JsonLines),
+            OutputFormat::Csv => Some(ExportFormat::Csv),
+        }
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, ValueEnum)]
+pub enum Stratify {
+    /// Uniformly random positions
+    None,
+    /// The same number of positions in every byte
+    Byte,
+    /// The same number of positions in every `--block-size` bytes
+    Block,
+}
+
+#[derive(Args)]
+pub struct InputArgs {
+    /// Input text; read from stdin when neither TEXT nor --file is given
+    pub text: Option<String>,
+
+    /// Read the input from a file, which may be binary
+    #[arg(short, long, conflicts_with = "text")]
+    pub file: Option<PathBuf>,
+
+    /// How to interpret the input; `raw` takes the bytes as they are
+    #[arg(short, long, value_enum, default_value_t = InputEncoding::Raw)]
+    pub encoding: InputEncoding,
+}
+
+#[derive(Clone, Copy, ValueEnum)]
+pub enum InputEncoding {
+    Raw,
+    Hex,
+    Base64,
+    /// Text with `\xNN`, `\n`, `\t`, `\0` and `\\` escapes
+    Escaped,
+}
+
+impl InputArgs {
+    fn read(&self) -> Result<Vec<u8>, Box<dyn Error>> {
+        let data = if let Some(text) = &self.text {
+            text.as_bytes().to_vec()
+        } else if let Some(path) = &self.file {
+            fs::read(path)?
+        } else {
+            let mut data = Vec::new();
+            io::stdin().read_to_end(&mut data)?;
+            data
+        };
+
+        let encoding = match self.encoding {
+            InputEncoding::Raw => return Ok(data),
+            InputEncoding::Hex => Encoding::Hex,
+            InputEncoding::Base64 => Encoding::Base64,
+            InputEncoding::Escaped => Encoding::Escaped,
+        };
+        let text = String::from_utf8(data)
+            .map_err(|_| format!("{} input must be valid UTF-8", encoding.name()))?;
+        encoding
+            .decode(&text)
+            .map_err(|err| format!("invalid {} input: {}", encoding.name(), err).into())
+    }
+
+    // Also rejects inputs too long for fixed-size targets such as cipher keys.
+    fn read_for(&self, algorithm: &dyn HashAlgorithm) -> Result<Vec<u8>, Box<dyn Error>> {
+        let data = self.read()?;
+        algorithms::check_input(algorithm, &data)?;
+        Ok(data)
+    }
+}
+
+#[derive(Args)]
+pub struct SeedArgs {
+    /// Seed for every random choice, decimal or 0x-prefixed hex; reports print the seed they used
+    #[arg(long, default_value_t = random::DEFAULT_SEED, value_parser = random::parse_seed)]
+    pub seed: u64,
+}
+
+#[derive(Clone, Copy, ValueEnum)]
+pub enum Generator {
+    /// Uniformly random bytes
+    Uniform,
+    /// Printable ASCII characters
+    Ascii,
+    /// Mostly zero bytes with about one bit in 32 set
+    Sparse,
+    /// Consecutive counter values, zero-padded on the left
+    Counter,
+}
+
+impl Generator {
+    fn kind(self) -> MessageKind {
+        match self {
+            Generator::Uniform => MessageKind::Uniform,
+            Generator::Ascii => MessageKind::Ascii,
+            Generator::Sparse => MessageKind::Sparse,
+            Generator::Counter => MessageKind::Counter,
+        }
+    }
+}
+
+#[derive(Args)]
+pub struct HashArgs {
+    /// Hash function or cipher target to use, see `list`
+    #[arg(short, long, default_value = "SHA-256")]
+    pub algorithm: String,
+}
+
+impl HashArgs {
+    fn resolve(&self) -> Result<&'static dyn HashAlgorithm, Box<dyn Error>> {
+        resolve_algorithm(&self.algorithm)
+    }
+}
+
+fn resolve_algorithm(name: &str) -> Result<&'static dyn HashAlgorithm, Box<dyn Error>> {
+    algorithms::find(name).ok_or_else(|| {
+        let known: Vec<&str> = ALGORITHMS.iter().map(|a| a.name()).collect();
+        format!(
+            "unknown hash function '{}', expected one of: {}",
+            name,
+            known.join(", ")
+        )
+        .into()
+    })
+}
+
+fn check_alpha(alpha: f64) -> Result<(), Box<dyn Error>> {
+    if alpha > 0.0 && alpha < 1.0 {
+        Ok(())
+    } else {
+        Err(format!("--alpha must be between 0 and 1, got {}", alpha).into())
+    }
+}
+
+pub fn run(command: Command, out: &mut dyn Write) -> Result<(), Box<dyn Error>> {
+    match command {
+        Command::Analyze {
+            input,
+            hash,
+            seed,
+            alpha,
+            sample,
+            ci_width,
+            stratify,
+            block_size,
+            exhaustive,
+            format,
+        } => {
+            check_alpha(alpha)?;
+            let algorithm = hash.resolve()?;
+            let data = input.read_for(algorithm)?;
+            if block_size == 0 {
+                return Err("--block-size must be at least 1".into());
+            }
+            if let Some(width) = ci_width
+                && !(width.is_finite() && width > 0.0)
+            {
+                return Err(format!("--ci-width must be greater than 0, got {}", width).into());
+            }
+            let strata = match stratify {
+                Stratify::None => Strata::Uniform,
+                Stratify::Byte => Strata::Bytes(1),
+                Stratify::Block => Strata::Bytes(block_size),
+            };
+            let target = match (sample, ci_width) {
+                (Some(count), _) => Some(Target::Count(count)),
+                (None, Some(width)) => Some(Target::Width(width)),
+                (None, None) => None,
+            };
+            let plan = SamplingPlan {
+                seed: seed.seed,
+                ..SamplingPlan::new(
+                    strata,
+                    target.unwrap_or(Target::Count(automatic::SAMPLED_FLIPS)),
+                )
+            };
+            let report = if target.is_some() || stratify != Stratify::None {
+                automatic::analyze_monte_carlo(algorithm, &data, &plan)
+            } else if exhaustive {
+                automatic::analyze(algorithm, &data)
+            } else {
+                automatic::analyze_auto(algorithm, &data, &plan)
+            };
+            if let Some(format) = format.export() {
+                RunExport::from_automatic(&report).write(format, out)?;
+                return Ok(());
+            }
+            for line in report.lines(alpha) {
+                writeln!(out, "{}", style_line(&line))?;
+            }
+        }
+        Command::Flip {
+            input,
+            hash,
+            seed,
+            bit,
+            format,
+        } => {
+            let data = input.read()?;
+            let (bits, random) = match bit {
+                Some(spec) => (spec.resolve(data.len() * 8, seed.seed)?, spec.is_random()),
+                None => (Vec::new(), false),
+            };
+            let report = manual::FlipReport {
+                seed: random.then_some(seed.seed),
+                ..manual::flip(hash.resolve()?, &data, &bits)?
+            };
+            if let Some(format) = format.export() {
+                RunExport::from_flip(&report, data.len()).write(format, out)?;
+                return Ok(());
+            }
+            writeln!(
+                out,
+                "{}",
+                style_line(&format!(
+                    "=== Hash comparison ({}) ===",
+                    report.algorithm.name()
+                ))
+            )?;
+            match report.bits[..] {
+                [] => writeln!(out, "Flipped bit: none")?,
+                [idx] => writeln!(out, "Flipped bit: {}", idx)?,
+                _ => writeln!(
+                    out,
+                    "Flipped bits: {} ({} bits)",
+                    flips::format_bits(&report.bits),
+                    report.bits.len()
+                )?,
+            }
+            if let Some(seed) = report.seed {
+                writeln!(out, "{}", flips::seed_line(seed))?;
+            }
+            writeln!(
+                out,
+                "Original hash: {}",
+                manipulations::bytes_to_hex(&report.original_digest)
+            )?;
+            writeln!(
+                out,
+                "New hash:      {}",
+                manipulations::bytes_to_hex(&report.flipped_digest)
+            )?;
+            let (original_colored, flipped_colored) = report.render();
+            writeln!(out, "Original bits: {}", paint(&original_colored))?;
+            writeln!(out, "New bits:      {}", paint(&flipped_colored))?;
+            writeln!(out, "Avalanche effect: {:.2}%", report.diff.percent())?;
+            writeln!(out, "{}", report.diff.summary_line())?;
+        }
+        Command::Sac {
+            input,
+            hash,
+            seed,
+            samples,
+            generator,
+        } => {
+            if samples == 0 {
+                return Err("--samples must be at least 1".into());
+            }
+            let algorithm = hash.resolve()?;
+            let message_len = input.read_for(algorithm)?.len();
+            if message_len > MAX_MATRIX_MESSAGE_LEN {
+                return Err(format!(
+                    "SAC analysis supports messages up to {} bytes, got {}",
+                    MAX_MATRIX_MESSAGE_LEN, message_len
+                )
+                .into());
+            }
+            let sac =
+                SacMatrix::compute(algorithm, message_len, samples, seed.seed, generator.kind());
+            for line in sac::summary_lines(&sac, algorithm) {
+                writeln!(out, "{}", style_line(&line))?;
+            }
+        }
+        Command::Sweep {
+            input,
+            hash,
+            seed,
+            max_weight,
+            trials,
+        } => {
+            if trials == 0 {
+                return Err("--trials must be at least 1".into());
+            }
+            let algorithm = hash.resolve()?;
+            let points = automatic::weight_sweep(
+                algorithm,
+                &input.read_for(algorithm)?,
+                max_weight,
+                trials,
+                seed.seed,
+            );
+            for line in automatic::sweep_lines(&points, seed.seed) {
+                writeln!(out, "{}", style_line(&line))?;
+            }
+        }
+        Command::Linearity {
+            input,
+            hash,
+            seed,
+            messages,
+        } => {
+            // One message has nothing to compare its output difference with.
+            if messages < 2 {
+                return Err("--messages must be at least 2".into());
+            }
+            let algorithm = hash.resolve()?;
+            let report =
+                linearity::detect(algorithm, &input.read_for(algorithm)?, messages, seed.seed);
+            for line in linearity::linearity_lines(&report) {
+                writeln!(out, "{}", style_line(&line))?;
+            }
+        }
+        Command::Bias {
+            input,
+            hash,
+            seed,
+            alpha,
+            messages,
+        } => {
+            check_alpha(alpha)?;
+            let algorithm = hash.resolve()?;
+            let counts = bias::output_bit_counts(
+                algorithm,
+                &input.read_for(algorithm)?,
+                messages,
+                seed.seed,
+            );
+            for line in bias::bias_lines(&counts, messages + 1, Some(seed.seed), alpha) {
+                writeln!(out, "{}", style_line(&line))?;
+            }
+            for row in bias::bias_strip(&counts, alpha) {
+                writeln!(out, "{}", paint(&row))?;
+            }
+        }
+        Command::Rounds { input } => {
+            let points = rounds::round_profile(&input.read()?);
+            for line in rounds::profile_lines(&points) {
+                writeln!(out, "{}", style_line(&line))?;
+            }
+            for row in rounds::profile_rows(&points) {
+                writeln!(out, "{}", paint(&row))?;
+            }
+        }
+        Command::Modes { input, seed, bit } => {
+            let data = input.read()?;
+            modes::check_message_len(data.len())?;
+            let bits = bit.resolve(data.len() * 8, seed.seed)?;
+            let results: Vec<_> = Mode::ALL
+                .iter()
+                .map(|&mode| modes::propagation(mode, &data, &bits))
+                .collect();
+            let random = bit.is_random().then_some(seed.seed);
+            for line in modes::summary_lines(data.len(), &bits, random) {
+                writeln!(out, "{}", style_line(&line))?;
+            }
+            for direction in [Direction::Encrypt, Direction::Decrypt] {
+                writeln!(out)?;
+                writeln!(out, "{}", modes::grid_title(direction))?;
+                for row in modes::grid_rows(&results, direction) {
+                    writeln!(out, "{}", paint(&row))?;
+                }
+            }
+        }
+        Command::Compare {
+            input,
+            seed,
+            algorithms,
+            alpha,
+        } => {
+            check_alpha(alpha)?;
+            let selected: Vec<&'static dyn HashAlgorithm> = if algorithms.is_empty() {
+                ALGORITHMS
+                    .iter()
+                    .copied()
+                    .filter(|algorithm| algorithm.input_len().is_none())
+                    .collect()
+            } else {
+                algorithms
+                    .iter()
+                    .map(|name| resolve_algorithm(name))
+                    .collect::<Result<_, _>>()?
+            };
+            write_comparison(&selected, &input.read()?, seed.seed, alpha, out)?;
+        }
+        Command::List => {
+            for algorithm in ALGORITHMS {
+                write!(
+                    out,
+                    "{:<14} {} bits",
+                    algorithm.name(),
+                    algorithm.output_bits()
+                )?;
+                match algorithm.input_len() {
+                    Some(len) => writeln!(out, ", input up to {} bytes", len)?,
+                    None => writeln!(out)?,
+                }
+            }
+        }
+        Command::Tui => unreachable!("the TUI is started by main"),
+    }
+    Ok(())
+}
+
+fn write_comparison(
+    selected: &[&'static dyn HashAlgorithm],
+    data: &[u8],
+    seed: u64,
+    alpha: f64,
+    out: &mut dyn Write,
+) -> Result<(), Box<dyn Error>> {
+    let plan = SamplingPlan {
+        seed,
+        ..SamplingPlan::default()
+    };
+    let comparison = compare::compare(selected, data, &plan, &Progress::none());
+    writeln!(out, "{}", compare::table_header().bold())?;
+    for row in compare::table_rows(&comparison, alpha) {
+        writeln!(out, "{}", paint(&row))?;
+    }
+    let paired = compare::paired_lines(&comparison, alpha);
+    if !paired.is_empty() {
+        writeln!(out)?;
+        writeln!(out, "{}", compare::heading(&comparison))?;
+    }
+    for line in paired {
+        writeln!(out, "{}", style_line(&line))?;
+    }
+    Ok(())
+}
+
+fn style_line(line: &str) -> String {
+    if line.starts_with("===") {
+        line.bold().to_string()
+    } else if let Some(rest) = line.strip_suffix("PASS") {
+        format!("{}{}", rest, "PASS".green().bold())
+    } else if let Some(rest) = line.strip_suffix("FAIL") {
+        format!("{}{}", rest, "FAIL".red().bold())
+    } else if let Some(rest) = line.strip_suffix("DIFFERENT") {
+        format!("{}{}", rest, "DIFFERENT".red().bold())
+    } else {
+        line.to_string()
+    }
+}
+
+fn paint(text: &[ColoredText]) -> String {
+    text.iter()
+        .map(|ct| match ct.color {
+            Color::Red => ct.text.red().to_string(),
+            Color::Yellow => ct.text.yellow().to_string(),
+            Color::Green => ct.text.green().to_string(),
+            _ => ct.text.clone(),
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn run_args(args: &[&str]) -> Result<String, Box<dyn Error>> {
+        colored::control::set_override(false);
+        let cli = Cli::try_parse_from(args)?;
+        let mut out = Vec::new();
+        run(cli.command.unwrap(), &mut out)?;
+        Ok(String::from_utf8(out).unwrap())
+    }
+
+    #[test]
+    fn test_no_subcommand_defaults_to_tui() {
+        let cli = Cli::try_parse_from(["avalanche_effect"]).unwrap();
+        assert!(cli.command.is_none());
+    }
+
+    #[test]
+    fn test_analyze_prints_summary() {
+        let output = run_args(&["avalanche_effect", "analyze", "hello"]).unwrap();
+        assert!(output.contains("=== Summary ==="));
+        assert!(output.contains("Hash function: SHA-256 (256 bits)"));
+        assert!(output.contains("Flips: 40"));
+        assert!(output.contains("Kolmogorov-Smirnov:"));
+    }
+
+    #[test]
+    fn test_analyze_with_algorithm_and_file() {
+        let path = std::env::temp_dir().join("avalanche_effect_cli_test.txt");
+        fs::write(&path, "abc").unwrap();
+
+        let output = run_args(&[
+            "avalanche_effect",
+            "analyze",
+            "--file",
+            path.to_str().unwrap(),
+            "-a",
+            "md5",
+        ])
+        .unwrap();
+        fs::remove_file(&path).unwrap();
+
+        assert!(output.contains("Hash function: MD5 (128 bits)"));
+        assert!(output.contains("Flips: 24"));
+    }
+
+    #[test]
+    fn test_analyze_stratified_sampling() {
+        let text = "a".repeat(256);
+        let output = run_args(&[
+            "avalanche_effect",
+            "analyze",
+            &text,
+            "--stratify",
+            "block",
+            "--block-size",
+            "16",
+            "--sample",
+            "300",
+        ])
+        .unwrap();
+        assert!(output.contains(
+            "Flips: 300 of 2048 input bits (random sample stratified by 16-byte block, seed"
+        ));
+        assert!(output.contains("Mean avalanche 95% CI: ["));
+
+        let output = run_args(&["avalanche_effect", "analyze", &text, "--ci-width", "3"]).unwrap();
+        assert!(output.contains("(random sample, seed"));
+        for width in ["0", "-1", "NaN", "inf"] {
+            let err = run_args(&[
+                "avalanche_effect",
+                "analyze",
+                &text,
+                &format!("--ci-width={}", width),
+            ])
+            .unwrap_err();
+            assert!(
+                err.to_string()
+                    .starts_with("--ci-width must be greater than 0"),
+                "{}",
+                width
+            );
+        }
+        assert!(
+            run_args(&[
+                "avalanche_effect",
+                "analyze",
+                "ab",
+                "--ci-width",
+                "1",
+                "--sample",
+                "4"
+            ])
+            .is_err()
+        );
+        assert!(
+            run_args(&[
+                "avalanche_effect",
+                "analyze",
+                "ab",
+                "--stratify",
+                "block",
+                "--block-size",
+                "0"
+            ])
+            .is_err()
+        );
+    }
+
+    #[test]
+    fn test_flip_prints_digests() {
+        let output = run_args(&["avalanche_effect", "flip", "abc", "--bit", "0"]).unwrap();
+        assert!(output.contains(
+            "Original hash: ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad"
+        ));
+        assert!(output.contains("Flipped bit: 0"));
+        assert!(output.contains("Avalanche effect:"));
+    }
+
+    #[test]
+    fn test_flip_out_of_range_is_an_error() {
+        let err = run_args(&["avalanche_effect", "flip", "a", "--bit", "8"]).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "Bit index out of range: 8 (input has 8 bits)"
+        );
+    }
+
+    #[test]
+    fn test_out_of_range_arguments_are_errors() {
+        for (args, message) in [
+            (
+                &["analyze", "abc", "--alpha", "2"][..],
+                "--alpha must be between 0 and 1, got 2",
+            ),
+            (
+                &["analyze", "abc", "--alpha", "0"],
+                "--alpha must be between 0 and 1, got 0",
+            ),
+            (
+                &["bias", "abc", "--alpha", "1"],
+                "--alpha must be between 0 and 1, got 1",
+            ),
+            (
+                &["compare", "abc", "--alpha=-0.5"],
+                "--alpha must be between 0 and 1, got -0.5",
+            ),
+            (
+                &["sweep", "abc", "--trials", "0"],
+                "--trials must be at least 1",
+            ),
+            (
+                &["linearity", "abc", "--messages", "0"],
+                "--messages must be at least 2",
+            ),
+            (
+                &["linearity", "abc", "--messages", "1"],
+                "--messages must be at least 2",
+            ),
+        ] {
+            let err = run_args(&[&["avalanche_effect"], args].concat()).unwrap_err();
+            assert_eq!(err.to_string(), message, "{:?}", args);
+        }
+    }
+
+    #[test]
+    fn test_sac_prints_matrix_summary() {
+        let output = run_args(&["avalanche_effect", "sac", "a", "--samples", "32"]).unwrap();
+        assert!(output.contains("Matrix: 8 input bits x 256 output bits"));
+        assert!(output.contains("Random messages: 32"));
+
+        let err = run_args(&["avalanche_effect", "sac", "a", "--samples", "0"]).unwrap_err();
+        assert_eq!(err.to_string(), "--samples must be at least 1");
+    }
+
+    #[test]
+    fn test_seed_and_generator_are_recorded() {
+        let args = [
+            "avalanche_effect",
+            "sac",
+            "ab",
+            "--samples",
+            "16",
+            "--generator",
+            "sparse",
+            "--seed",
+            "0x2a",
+        ];
+        let output = run_args(&args).unwrap();
+        assert!(output.contains("Random messages: 16 sparse (seed 0x2a)"));
+        assert_eq!(output, run_args(&args).unwrap());
+
+        let decimal = run_args(&[
+            "avalanche_effect",
+            "sac",
+            "ab",
+            "--samples",
+            "16",
+            "--generator",
+            "sparse",
+            "--seed",
+            "42",
+        ])
+        .unwrap();
+        assert_eq!(output, decimal);
+
+        let sweep = run_args(&["avalanche_effect", "sweep", "ab", "--seed", "7"]).unwrap();
+        assert!(sweep.contains("Random differences (seed 0x7)"));
+        assert!(run_args(&["avalanche_effect", "sweep", "ab", "--seed", "x"]).is_err());
+    }
+
+    #[test]
+    fn test_compare_prints_one_row_per_algorithm() {
+        let output = run_args(&[
+            "avalanche_effect",
+            "compare",
+            "abc",
+            "--algorithms",
+            "SHA-256,BLAKE3",
+        ])
+        .unwrap();
+        let lines: Vec<&str> = output.lines().collect();
+        assert_eq!(lines.len(), 7);
+        assert!(lines[1].starts_with("SHA-256"));
+        assert!(lines[2].starts_with("BLAKE3"));
+        assert!(lines[2].ends_with("/s"));
+        assert_eq!(lines[4], "Same 24 flips for every hash function");
+        assert!(lines[6].starts_with("SHA-256 vs BLAKE3: mean difference"));
+    }
+
+    #[test]
+    fn test_analyze_json_output() {
+        let output = run_args(&["avalanche_effect", "analyze", "ab", "--format", "json"]).unwrap();
+        let value: serde_json::Value = serde_json::from_str(&output).unwrap();
+        assert_eq!(value["flips"].as_array().unwrap().len(), 16);
+    }
+
+    #[test]
+    fn test_analyze_jsonl_and_csv_output() {
+        let jsonl = run_args(&["avalanche_effect", "analyze", "ab", "--format", "jsonl"]).unwrap();
+        assert_eq!(jsonl.lines().count(), 16);
+
+        let csv = run_args(&["avalanche_effect", "analyze", "ab", "--format", "csv"]).unwrap();
+        assert_eq!(csv.lines().count(), 17);
+        assert!(csv.starts_with("algorithm,bit_index,"));
+    }
+
+    #[test]
+    fn test_flip_json_output() {
+        let output = run_args(&[
+            "avalanche_effect",
+            "flip",
+            "abc",
+            "--bit",
+            "1",
+            "--format",
+            "json",
+        ])
+        .unwrap();
+        let value: serde_json::Value = serde_json::from_str(&output).unwrap();
+        assert_eq!(value["flips"][0]["bit_index"], 1);
+        assert_eq!(
+            value["flips"][0]["original_digest"],
+            "ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad"
+        );
+    }
+
+    #[test]
+    fn test_analyze_binary_file() {
+        let path = std::env::temp_dir().join("avalanche_effect_cli_binary.bin");
+        fs::write(&path, [0xff, 0xfe, 0x00, 0x80]).unwrap();
+
+        let output = run_args(&[
+            "avalanche_effect",
+            "analyze",
+            "--file",
+            path.to_str().unwrap(),
+        ])
+        .unwrap();
+        assert!(output.contains("Flips: 32"));
+
+        fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn test_analyze_sample_option() {
+        let output =
+            run_args(&["avalanche_effect", "analyze", "abcdef", "--sample", "10"]).unwrap();
+        assert!(output.contains("Flips: 10 of 48 input bits (random sample"));
+
+        assert!(
+            run_args(&[
+                "avalanche_effect",
+                "analyze",
+                "ab",
+                "--sample",
+                "3",
+                "--exhaustive"
+            ])
+            .is_err()
+        );
+    }
+
+    #[test]
+    fn test_input_encodings() {
+        let hex = run_args(&[
+            "avalanche_effect",
+            "flip",
+            "616263",
+            "-e",
+            "hex",
+            "--bit",
+            "1",
+        ])
+        .unwrap();
+        let text = run_args(&["avalanche_effect", "flip", "abc", "--bit", "1"]).unwrap();
+        assert_eq!(hex, text);
+
+        let base64 =
+            run_args(&["avalanche_effect", "analyze", "AAAAAA==", "-e", "base64"]).unwrap();
+        assert!(base64.contains("Flips: 32"));
+
+        let escaped =
+            run_args(&["avalanche_effect", "analyze", "\\x00\\xff", "-e", "escaped"]).unwrap();
+        assert!(escaped.contains("Flips: 16"));
+
+        let err = run_args(&["avalanche_effect", "analyze", "xyz", "-e", "hex"]).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "invalid Hex input: odd number of hex digits"
+        );
+    }
+
+    #[test]
+    fn test_flip_spec_option() {
+        let output =
+            run_args(&["avalanche_effect", "flip", "abcd", "--bits", "0-7,byte:3"]).unwrap();
+        assert!(output.contains("Flipped bits: 0-7, 24-31 (16 bits)"));
+        assert!(!output.contains("Random bits drawn"));
+
+        let flip = run_args(&[
+            "avalanche_effect",
+            "flip",
+            "abcd",
+            "--bits",
+            "random:2",
+            "--seed",
+            "0x5eed",
+        ])
+        .unwrap();
+        assert!(flip.contains("Random bits drawn with seed 0x5eed"));
+        let modes = run_args(&[
+            "avalanche_effect",
+            "modes",
+            "abcd",
+            "--bit",
+            "random:2",
+            "--seed",
+            "0x5eed",
+        ])
+        .unwrap();
+        assert!(modes.contains("Random bits drawn with seed 0x5eed"));
+
+        let err = run_args(&["avalanche_effect", "flip", "ab", "--bit", "burst:4@14"]).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "Bit index out of range: 17 (input has 16 bits)"
+        );
+
+        assert!(run_args(&["avalanche_effect", "flip", "ab", "--bit", "x"]).is_err());
+    }
+
+    #[test]
+    fn test_sweep_prints_each_weight() {
+        let output = run_args(&[
+            "avalanche_effect",
+            "sweep",
+            "abc",
+            "--max-weight",
+            "4",
+            "--trials",
+            "8",
+        ])
+        .unwrap();
+        assert!(output.contains("=== Avalanche by input difference weight ==="));
+        assert!(output.contains("k =  4: mean"));
+        assert!(!output.contains("k =  5"));
+    }
+
+    #[test]
+    fn test_rounds_prints_profile() {
+        let output = run_args(&["avalanche_effect", "rounds", "abc"]).unwrap();
+        assert!(output.contains("=== SHA-256 avalanche by round count ==="));
+        assert_eq!(output.lines().filter(|l| l.contains(" | ")).count(), 64);
+
+        let reduced =
+            run_args(&["avalanche_effect", "analyze", "abc", "-a", "sha-256-r4"]).unwrap();
+        assert!(reduced.contains("Hash function: SHA-256-R4 (256 bits)"));
+    }
+
+    #[test]
+    fn test_unknown_algorithm_is_an_error() {
+        let err = run_args(&["avalanche_effect", "analyze", "abc", "-a", "nope"]).unwrap_err();
+        assert!(err.to_string().contains("unknown hash function 'nope'"));
+    }
+
+    #[test]
+    fn test_analyze_cipher_key_and_length_check() {
+        let output = run_args(&[
+            "avalanche_effect",
+            "analyze",
+            "secret",
+            "--algorithm",
+            "aes-128-key",
+        ])
+        .unwrap();
+        assert!(output.contains("AES-128-key"));
+
+        let err = run_args(&[
+            "avalanche_effect",
+            "analyze",
+            "a message longer than one block",
+            "--algorithm",
+            "AES-256",
+        ])
+        .unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "AES-256 takes at most 16 bytes of input, got 31"
+        );
+    }
+
+    #[test]
+    fn test_compare_reports_too_long_cipher_input() {
+        let output = run_args(&[
+            "avalanche_effect",
+            "compare",
+            "a message longer than one block",
+            "--algorithms",
+            "SHA-256,AES-128",
+        ])
+        .unwrap();
+        let lines: Vec<&str> = output.lines().collect();
+        assert!(lines[1].starts_with("SHA-256"));
+        assert!(lines[2].ends_with("AES-128 takes at most 16 bytes of input, got 31"));
+    }
+
+    #[test]
+    fn test_modes_prints_both_grids() {
+        let output = run_args(&[
+            "avalanche_effect",
+            "modes",
+            "0123456789abcdef0123456789abcdef",
+            "--bit",
+            "200",
+        ])
+        .unwrap();
+        assert!(output.contains("Flipped bits: 200 (block 1)"));
+        assert!(output.contains("   ECB |   "));
+        assert_eq!(output.matches("   CTR |    1   0").count(), 2);
+
+        assert!(run_args(&["avalanche_effect", "modes", "ab", "--bit", "16"]).is_err());
+    }
+
+    #[test]
+    fn test_linearity_contrasts_crc_with_sha256() {
+        let crc = run_args(&["avalanche_effect", "linearity", "abc", "-a", "crc-32"]).unwrap();
+        assert!(crc.contains("24 of 24 input bits across 8 messages -> FAIL"));
+        assert!(crc.contains("Flipping bit 0 always XORs the output with "));
+
+        let sha = run_args(&["avalanche_effect", "linearity", "abc"]).unwrap();
+        assert!(sha.contains("0 of 24 input bits across 8 messages -> PASS"));
+    }
+
+    #[test]
+    fn test_bias_flags_fnv_but_not_sha256() {
+        let fnv = run_args(&["avalanche_effect", "bias", "abcd", "-a", "fnv-1a-32"]).unwrap();
+        assert!(fnv.contains("Flips: 512 over 16 messages"));
+        assert!(fnv.contains(" of 32 -> FAIL"));
+
+        let sha = run_args(&["avalanche_effect", "bias", "abcd", "--messages", "3"]).unwrap();
+        assert!(sha.contains("Bonferroni over 256 bits"));
+        assert!(sha.contains("Biased output bits: 0 of 256 -> PASS"));
+    }
+
+    #[test]
+    fn test_list_prints_registry() {
+        let output = run_args(&["avalanche_effect", "list"]).unwrap();
+        assert_eq!(output.lines().count(), ALGORITHMS.len());
+        assert!(output.contains("SHA-512"));
+        assert!(output.contains("ChaCha20-key   512 bits, input up to 32 bytes"));
+    }
+
+    #[test]
+    fn test_style_line_without_color() {
+        colored::control::set_override(false);
+        assert_eq!(
+            style_line("Chi-square: ... -> PASS"),
+            "Chi-square: ... -> PASS"
+        );
+        assert_eq!(style_line("=== Summary ==="), "=== Summary ===");
+    }
+}
@@ -1,28 +1,53 @@
 use crate::{
-    app::{App, CurrentScreen, SandboxMode},
+    cli::{Cli, Command},
     ui::ui,
 };
+use avalanche_effect::app::{App, CurrentScreen, InputState, SandboxMode, sha256};
+use clap::Parser;
 use ratatui::{
     Terminal,
     backend::{Backend, CrosstermBackend},
     crossterm::{
-        event::{self, DisableMouseCapture, EnableMouseCapture, Event, KeyCode, KeyEventKind},
+        event::{
+            self, DisableMouseCapture, EnableMouseCapture, Event, KeyCode, KeyEventKind,
+            KeyModifiers,
+        },
         execute,
         terminal::{EnterAlternateScreen, LeaveAlternateScreen, disable_raw_mode, enable_raw_mode},
     },
 };
-use std::{error::Error, io};
+use std::{
+    error::Error,
+    io,
+    path::Path,
+    time::{Duration, Instant},
+};
 
-mod app;
+mod cli;
 mod ui;
 
+const TICK_RATE: Duration = Duration::from_millis(120);
+
 fn main() -> Result<(), Box<dyn Error>> {
+    let cli = Cli::parse();
+    if cli.no_color {
+        colored::control::set_override(false);
+    }
+
+    match cli.command {
+        None | Some(Command::Tui) => run_tui(),
+        Some(command) => cli::run(command, &mut io::stdout().lock()),
+    }
+}
+
+fn run_tui() -> Result<(), Box<dyn Error>> {
     enable_raw_mode()?;
     let mut stderr = io::stderr();
     execute!(stderr, EnterAlternateScreen, EnableMouseCapture)?;
     let backend = CrosstermBackend::new(stderr);
     let mut terminal = Terminal::new(backend)?;
     let mut app = App::new();
+    app.run_in_background = true;
 
     let res = run_app(&mut terminal, &mut app);
 
@@ -42,7 +67,9 @@ fn main() -> Result<(), Box<dyn Error>> {
 }
 
 fn run_app<B: Backend>(terminal: &mut Terminal<B>, app: &mut App) -> io::Result<bool> {
+    let mut last_tick = Instant::now();
     loop {
+        app.poll_job();
         terminal.draw(|f| {
             if app.input_state.is_some() {
                 let input_width = f.area().width as usize;
@@ -51,11 +78,29 @@ fn run_app<B: Backend>(terminal: &mut Terminal<B>, app: &mut App) -> io::Result<
             ui(f, app)
         })?;
 
-        if let Event::Key(key) = event::read()? {
+        if last_tick.elapsed() >= TICK_RATE {
+            app.on_tick();
+            last_tick = Instant::now();
+        }
+
+        let timeout = TICK_RATE.saturating_sub(last_tick.elapsed());
+        if event::poll(timeout)?
+            && let Event::Key(key) = event::read()?
+        {
             if key.kind == KeyEventKind::Release {
                 continue;
             }
 
+            // Only cancelling is possible while an analysis runs.
+            if app.job_running() {
+                let ctrl_c =
+                    key.code == KeyCode::Char('c') && key.modifiers.contains(KeyModifiers::CONTROL);
+                if key.code == KeyCode::Esc || ctrl_c {
+                    app.cancel_job();
+                }
+                continue;
+            }
+
             if key.code == KeyCode::Esc {
                 match app.current_screen {
                     CurrentScreen::Sandbox => {
@@ -76,6 +121,48 @@ fn run_app<B: Backend>(terminal: &mut Terminal<B>, app: &mut App) -> io::Result<
                     KeyCode::Char('m') => {
                         app.switch_to_manual();
                     }
+                    KeyCode::Char('s') => {
+                        app.switch_to_sac();
+                    }
+                    KeyCode::Char('b') => {
+                        app.switch_to_bic();
+                    }
+                    KeyCode::Char('r') => {
+                        app.switch_to_rounds();
+                    }
+                    KeyCode::Char('d') => {
+                        app.switch_to_diffusion();
+                    }
+                    KeyCode::Char('o') => {
+                        app.switch_to_modes();
+                    }
+                    KeyCode::Char('l') => {
+                        app.cycle_significance_level();
+                    }
+                    KeyCode::Char('c') => {
+                        app.switch_to_compare();
+                    }
+                    KeyCode::Char('k') => {
+                        app.toggle_compared();
+                    }
+                    KeyCode::Char('p') => {
+                        app.cycle_sampling();
+                    }
+                    KeyCode::Char('n') => {
+                        app.next_seed();
+                    }
+                    KeyCode::Char('g') => {
+                        app.cycle_message_kind();
+                    }
+                    KeyCode::Char('x') => {
+                        app.cycle_export_format();
+                    }
+                    KeyCode::Left => {
+                        app.select_previous_algorithm();
+                    }
+                    KeyCode::Right => {
+                        app.select_next_algorithm();
+                    }
                     KeyCode::Up => {
                         app.scroll_output_up();
                     }
@@ -96,9 +183,32 @@ fn run_app<B: Backend>(terminal: &mut Terminal<B>, app: &mut App) -> io::Result<
                 CurrentScreen::Sandbox => {
                     if app.input_state.is_some() {
                         match key.code {
+                            KeyCode::Char('e')
+                                if matches!(app.input_state, Some(InputState::ShowingResult)) =>
+                            {
+                                app.export_results(Path::new("."));
+                            }
+                            KeyCode::Char(' ') if app.diffusion_active() => {
+                                app.toggle_diffusion_playback();
+                            }
+                            KeyCode::Right if app.diffusion_active() => {
+                                app.step_diffusion(1);
+                            }
+                            KeyCode::Left if app.diffusion_active() => {
+                                app.step_diffusion(-1);
+                            }
+                            KeyCode::Home if app.diffusion_active() => {
+                                app.step_diffusion(-(sha256::MAX_ROUNDS as isize));
+                            }
+                            KeyCode::End if app.diffusion_active() => {
+                                app.step_diffusion(sha256::MAX_ROUNDS as isize);
+                            }
                             KeyCode::Char(c) => {
                                 app.handle_input(c);
                             }
+                            KeyCode::Tab => {
+                                app.toggle_input_source();
+                            }
 
                             KeyCode::Backspace => {
                                 app.handle_backspace();
@@ -145,6 +255,24 @@ fn run_app<B: Backend>(terminal: &mut Terminal<B>, app: &mut App) -> io::Result<
                                     app.switch_to_manual();
                                 }
                                 Some(SandboxMode::Manual) => {
+                                    app.switch_to_sac();
+                                }
+                                Some(SandboxMode::Sac) => {
+                                    app.switch_to_bic();
+                                }
+                                Some(SandboxMode::Bic) => {
+                                    app.switch_to_rounds();
+                                }
+                                Some(SandboxMode::Rounds) => {
+                                    app.switch_to_diffusion();
+                                }
+                                Some(SandboxMode::Diffusion) => {
+                                    app.switch_to_modes();
+                                }
+                                Some(SandboxMode::Modes) => {
+                                    app.switch_to_compare();
+                                }
+                                Some(SandboxMode::Compare) => {
                                     app.switch_to_automatic();
                                 }
                                 None => {
@@ -1,13 +1,29 @@
-use crate::app::{App, CurrentScreen, InputState, SandboxMode};
+use avalanche_effect::app::{
+    App, CurrentScreen, InputState, SandboxMode,
+    compare::{self, Comparison},
+    diffusion::{DiffusionView, VARIABLE_NAMES},
+    flips,
+    histogram::DistanceHistogram,
+    statistics,
+};
 use ratatui::{
     Frame,
+    buffer::Buffer,
     layout::{Alignment, Constraint, Direction, Layout, Rect},
     style::{Color, Style, Stylize},
+    symbols::Marker,
     text::{Line, Span, Text},
-    widgets::{Block, Borders, Clear, Paragraph, Wrap},
+    widgets::{
+        Axis, Block, Borders, Cell, Chart, Clear, Dataset, Gauge, GraphType, LegendPosition,
+        Paragraph, Row, Sparkline, Table, Widget, Wrap,
+    },
 };
 
 static LOGO_COLOR: Color = Color::Rgb(180, 220, 255);
+// Rows given to the histogram; the report keeps the rest and scrolls.
+const HISTOGRAM_HEIGHT: u16 = 12;
+// Below this the report text would get too little room next to the chart.
+const HISTOGRAM_MIN_HEIGHT: u16 = 24;
 
 pub fn ui(frame: &mut Frame, app: &App) {
     match app.current_screen {
@@ -59,6 +75,53 @@ fn render_menu(frame: &mut Frame, app: &App) {
         Line::from("Choose your mode:"),
         Line::from("• Manual    - Enter a string and flip one chosen bit"),
         Line::from("• Automatic - Enter a string, flip all bits in turn "),
+        Line::from("• SAC       - Strict Avalanche Criterion matrix      "),
+        Line::from("• BIC       - Bit Independence Criterion correlations"),
+        Line::from("• Rounds    - SHA-256 avalanche as rounds are added  "),
+        Line::from("• Diffusion - SHA-256 state animated round by round  "),
+        Line::from("• Modes     - AES block modes: which blocks a flip hits"),
+        Line::from("• Compare   - Same flips against several hash functions"),
+        Line::from(""),
+        Line::from(vec![
+            Span::raw("Hash function: "),
+            Span::styled(
+                format!("◀ {} ▶", app.algorithm().name()),
+                Style::default().fg(LOGO_COLOR).bold(),
+            ),
+            Span::raw("   Significance level: "),
+            Span::styled(
+                format!("{}", app.significance_level),
+                Style::default().fg(LOGO_COLOR).bold(),
+            ),
+            Span::raw("   Export: "),
+            Span::styled(
+                app.export_format.name(),
+                Style::default().fg(LOGO_COLOR).bold(),
+            ),
+        ]),
+        Line::from(vec![
+            Span::raw("Sampling of large inputs: "),
+            Span::styled(app.sampling.name(), Style::default().fg(LOGO_COLOR).bold()),
+        ]),
+        Line::from(vec![
+            Span::raw("Seed: "),
+            Span::styled(
+                format!("{:#x}", app.seed),
+                Style::default().fg(LOGO_COLOR).bold(),
+            ),
+            Span::raw("   Random messages: "),
+            Span::styled(
+                app.message_kind.name(),
+                Style::default().fg(LOGO_COLOR).bold(),
+            ),
+        ]),
+        Line::from(vec![
+            Span::raw("Compared: "),
+            Span::styled(
+                app.compared_names().join(", "),
+                Style::default().fg(LOGO_COLOR).bold(),
+            ),
+        ]),
     ];
 
     let mut all_lines = logo_spans;
@@ -103,9 +166,40 @@ fn render_sandbox(frame: &mut Frame, app: &App) {
 
     frame.render_widget(title, chunks[0]);
 
-    render_output_area(frame, app, chunks[1]);
+    match (&app.diffusion, &app.comparison) {
+        (Some(view), _) if app.diffusion_active() => {
+            frame.render_widget(DiffusionWidget { view }, chunks[1]);
+        }
+        (_, Some(comparison)) if app.comparison_active() => {
+            frame.render_widget(
+                ComparisonWidget {
+                    comparison,
+                    significance_level: app.significance_level,
+                },
+                chunks[1],
+            );
+        }
+        _ => match &app.histogram {
+            Some(histogram) if chunks[1].height >= HISTOGRAM_MIN_HEIGHT => {
+                let output = Layout::default()
+                    .direction(Direction::Vertical)
+                    .constraints([Constraint::Min(10), Constraint::Length(HISTOGRAM_HEIGHT)])
+                    .split(chunks[1]);
+                render_output_area(frame, app, output[0]);
+                frame.render_widget(HistogramWidget { histogram }, output[1]);
+            }
+            _ => render_output_area(frame, app, chunks[1]),
+        },
+    }
 
-    if app.input_state.is_some() {
+    if let Some(job) = &app.job {
+        let gauge = Gauge::default()
+            .block(Block::default().borders(Borders::ALL).title("Progress"))
+            .gauge_style(Style::default().fg(LOGO_COLOR))
+            .ratio(job.ratio())
+            .label(job.label());
+        frame.render_widget(gauge, chunks[2]);
+    } else if app.input_state.is_some() {
         render_input_area(frame, app, chunks[2]);
     }
 
@@ -138,11 +232,275 @@ fn render_output_area(frame: &mut Frame, app: &App, area: Rect) {
                 .fg(LOGO_COLOR),
         )
         .alignment(Alignment::Left)
-        .wrap(Wrap { trim: true });
+        .wrap(Wrap { trim: true })
+        .scroll((app.output_scroll_offset as u16, 0));
 
     frame.render_widget(paragraph, area);
 }
 
+struct DiffusionWidget<'a> {
+    view: &'a DiffusionView,
+}
+
+impl Widget for DiffusionWidget<'_> {
+    fn render(self, area: Rect, buf: &mut Buffer) {
+        let view = self.view;
+        let round = view.round;
+        let differing = view.differing_bits(round);
+
+        let block = Block::default()
+            .borders(Borders::ALL)
+            .title("SHA-256 round by round")
+            .fg(LOGO_COLOR);
+        let inner = block.inner(area);
+        block.render(area, buf);
+
+        let chunks = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([Constraint::Length(14), Constraint::Min(0)])
+            .split(inner);
+
+        let mut lines = vec![
+            Line::from(format!(
+                "Round {:>2}/{}   block {} of {}   {}",
+                round,
+                view.last_round(),
+                view.block + 1,
+                view.blocks,
+                if view.playing { "playing" } else { "paused" }
+            )),
+            Line::from(format!(
+                "Flipped input bits: {}",
+                if view.bits.is_empty() {
+                    "none".to_string()
+                } else {
+                    flips::format_bits(&view.bits)
+                }
+            )),
+            Line::from(""),
+            Line::from(format!("   {:<34}{:<34}diff", "original", "flipped")),
+        ];
+        for (i, name) in VARIABLE_NAMES.iter().enumerate() {
+            let original = view.original[round][i];
+            let flipped = view.flipped[round][i];
+            let diff = original ^ flipped;
+
+            let mut spans = vec![Span::raw(format!("{}  ", name))];
+            spans.extend(word_spans(original, diff));
+            spans.push(Span::raw("  "));
+            spans.extend(word_spans(flipped, diff));
+            spans.push(Span::raw(format!("  {:>2}", diff.count_ones())));
+            lines.push(Line::from(spans));
+        }
+        lines.push(Line::from(""));
+        lines.push(Line::from(format!(
+            "Differing bits: {}/256 ({:.1}%)",
+            differing,
+            differing as f64 / 256.0 * 100.0
+        )));
+        Paragraph::new(lines).render(chunks[0], buf);
+
+        let history: Vec<u64> = (0..=round).map(|r| view.differing_bits(r) as u64).collect();
+        Sparkline::default()
+            .data(&history)
+            .max(256)
+            .style(Style::default().fg(Color::Yellow))
+            .render(chunks[1], buf);
+    }
+}
+
+// Observed Hamming distances as bars, one bin per terminal column, with the
+// binomial counts of an ideal hash as a line on top.
+struct HistogramWidget<'a> {
+    histogram: &'a DistanceHistogram,
+}
+
+impl Widget for HistogramWidget<'_> {
+    fn render(self, area: Rect, buf: &mut Buffer) {
+        let histogram = self.histogram;
+        // Borders and the y axis labels take about ten columns.
+        let bins = histogram.bins(area.width.saturating_sub(10) as usize);
+        let (start, end) = histogram.window();
+        // Empty bins would still leave a dot on the axis.
+        let observed: Vec<(f64, f64)> = bins
+            .iter()
+            .filter(|bin| bin.observed > 0)
+            .map(|bin| (bin.start as f64, bin.observed as f64))
+            .collect();
+        let expected: Vec<(f64, f64)> = bins
+            .iter()
+            .map(|bin| (bin.start as f64, bin.expected))
+            .collect();
+        let top = bins
+            .iter()
+            .map(|bin| (bin.observed as f64).max(bin.expected))
+            .fold(1.0, f64::max)
+            .ceil();
+        let mean = histogram.ideal().mean();
+
+        let datasets = vec![
+            Dataset::default()
+                .name("observed")
+                .marker(Marker::HalfBlock)
+                .graph_type(GraphType::Bar)
+                .style(Style::default().fg(Color::Yellow))
+                .data(&observed),
+            Dataset::default()
+                .name("binomial")
+                .marker(Marker::Braille)
+                .graph_type(GraphType::Line)
+                .style(Style::default().fg(Color::Cyan))
+                .data(&expected),
+        ];
+        Chart::new(datasets)
+            .block(
+                Block::default()
+                    .borders(Borders::ALL)
+                    .title(format!("Hamming distances over {} flips", histogram.flips))
+                    .fg(LOGO_COLOR),
+            )
+            .x_axis(
+                Axis::default()
+                    .bounds([start as f64, (end - 1) as f64])
+                    .labels([
+                        start.to_string(),
+                        format!("{}", mean),
+                        (end - 1).to_string(),
+                    ]),
+            )
+            .y_axis(
+                Axis::default()
+                    .bounds([0.0, top])
+                    .labels(["0".to_string(), format!("{}", top)]),
+            )
+            .legend_position(Some(LegendPosition::TopRight))
+            .hidden_legend_constraints((Constraint::Min(0), Constraint::Min(0)))
+            .render(area, buf);
+    }
+}
+
+// One column per hash function, one row per statistic, and the paired tests
+// below the table.
+struct ComparisonWidget<'a> {
+    comparison: &'a Comparison,
+    significance_level: f64,
+}
+
+impl Widget for ComparisonWidget<'_> {
+    fn render(self, area: Rect, buf: &mut Buffer) {
+        let comparison = self.comparison;
+        let block = Block::default()
+            .borders(Borders::ALL)
+            .title("Side-by-side comparison")
+            .fg(LOGO_COLOR);
+        let inner = block.inner(area);
+        block.render(area, buf);
+
+        let reports = &comparison.reports;
+        let summaries: Vec<_> = reports.iter().map(|report| report.summary()).collect();
+        let p_values: Vec<_> = reports.iter().map(compare::p_value_cells).collect();
+
+        let number = |value: Option<f64>, digits: usize| -> Cell {
+            Cell::from(value.map_or("-".to_string(), |v| format!("{:.*}", digits, v)))
+        };
+        let statistic = |name: &'static str, pick: fn(&statistics::Summary) -> f64| -> Row {
+            let mut cells = vec![Cell::from(name)];
+            cells.extend(summaries.iter().map(|s| number(s.as_ref().map(pick), 2)));
+            Row::new(cells)
+        };
+        let p_value = |name: &'static str, test: usize| -> Row {
+            let mut cells = vec![Cell::from(name)];
+            cells.extend(p_values.iter().map(|p| {
+                let color = match p[test] {
+                    Some(p) if p >= self.significance_level => Color::Green,
+                    Some(_) => Color::Red,
+                    None => Color::Gray,
+                };
+                number(p[test], 4).style(Style::default().fg(color))
+            }));
+            Row::new(cells)
+        };
+
+        let mut header = vec![Cell::from("")];
+        header.extend(reports.iter().map(|r| Cell::from(r.algorithm.name())));
+        let mut bits = vec![Cell::from("Output bits")];
+        bits.extend(
+            reports
+                .iter()
+                .map(|r| Cell::from(r.algorithm.output_bits().to_string())),
+        );
+        let mut rate = vec![Cell::from("Throughput")];
+        rate.extend(
+            reports
+                .iter()
+                .map(|r| Cell::from(compare::format_rate(compare::throughput(r)))),
+        );
+        let rows = vec![
+            Row::new(bits),
+            statistic("Mean %", |s| s.mean),
+            statistic("Std %", |s| s.std_dev),
+            statistic("Min %", |s| s.min),
+            statistic("Max %", |s| s.max),
+            p_value("Chi-square p", 0),
+            p_value("KS p", 1),
+            Row::new(rate),
+        ];
+        let row_count = rows.len() as u16;
+
+        let mut widths = vec![Constraint::Length(13)];
+        widths.extend(reports.iter().map(|_| Constraint::Fill(1)));
+        let table = Table::new(rows, widths)
+            .header(Row::new(header).style(Style::default().bold()))
+            .column_spacing(1);
+
+        let chunks = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([
+                Constraint::Length(2),
+                Constraint::Length(row_count + 2),
+                Constraint::Min(0),
+            ])
+            .split(inner);
+
+        Paragraph::new(compare::heading(comparison)).render(chunks[0], buf);
+        Widget::render(table, chunks[1], buf);
+
+        let mut lines: Vec<Line> = comparison
+            .skipped
+            .iter()
+            .map(|(name, err)| Line::from(format!("{}: {}", name, err)))
+            .collect();
+        for line in compare::paired_lines(comparison, self.significance_level) {
+            let color = if line.ends_with("DIFFERENT") {
+                Color::Red
+            } else if line.ends_with("no difference") {
+                Color::Green
+            } else {
+                LOGO_COLOR
+            };
+            lines.push(Line::styled(line, Style::default().fg(color)));
+        }
+        Paragraph::new(lines)
+            .wrap(Wrap { trim: true })
+            .render(chunks[2], buf);
+    }
+}
+
+fn word_spans(word: u32, diff: u32) -> Vec<Span<'static>> {
+    (0..32)
+        .rev()
+        .map(|bit| {
+            let value = if word >> bit & 1 == 1 { "1" } else { "0" };
+            let color = if diff >> bit & 1 == 1 {
+                Color::Red
+            } else {
+                Color::Gray
+            };
+            Span::styled(value, Style::default().fg(color))
+        })
+        .collect()
+}
+
 fn render_input_area(frame: &mut Frame, app: &App, area: Rect) {
     let prompt = app.get_input_prompt();
     let prompt_len = prompt.len();
@@ -228,11 +586,18 @@ fn render_status_bar(frame: &mut Frame, app: &App, area: Rect) {
         )],
         CurrentScreen::Sandbox => vec![Span::styled(
             format!(
-                "Screen: Analyzer, Mode: {}",
+                "Screen: Analyzer, Mode: {}, Hash: {}",
                 app.current_mode.as_ref().map_or("Unknown", |m| match m {
                     SandboxMode::Manual => "Manual",
                     SandboxMode::Automatic => "Automatic",
-                })
+                    SandboxMode::Sac => "SAC",
+                    SandboxMode::Bic => "BIC",
+                    SandboxMode::Rounds => "Rounds",
+                    SandboxMode::Diffusion => "Diffusion",
+                    SandboxMode::Modes => "Modes",
+                    SandboxMode::Compare => "Compare",
+                }),
+                app.algorithm().name()
             ),
             Style::default().fg(LOGO_COLOR),
         )],
@@ -251,22 +616,32 @@ fn render_status_bar(frame: &mut Frame, app: &App, area: Rect) {
     let current_keys_hint = {
         match app.current_screen {
             CurrentScreen::Menu => Span::styled(
-                "(a) Automatic Mode / (m) Manual Mode / (q) Quit",
+                "(a) Automatic / (m) Manual / (s) SAC / (b) BIC / (r) Rounds / (d) Diffusion / (o) Modes / (c) Compare / (k) Compare hash / ←→ Hash / (l) Alpha / (p) Sampling / (n) New seed / (g) Messages / (x) Export format / (q) Quit",
                 Style::default().fg(LOGO_COLOR),
             ),
+            CurrentScreen::Sandbox if app.job_running() => {
+                Span::styled("Esc / Ctrl+C cancel", Style::default().fg(LOGO_COLOR))
+            }
             CurrentScreen::Sandbox => {
                 if let Some(input_state) = &app.input_state {
                     match input_state {
                         InputState::EnteringText => Span::styled(
-                            "Type and Enter / Esc menu",
+                            format!(
+                                "Type and Enter / Tab input: {} / Esc menu",
+                                app.input_source.name()
+                            ),
                             Style::default().fg(LOGO_COLOR),
                         ),
                         InputState::EnteringBitIndex => Span::styled(
-                            "Enter bit index and Enter / Esc menu",
+                            "Bit index or list (0-7,12 / byte:2 / burst:4@10 / random:3) and Enter / Esc menu",
+                            Style::default().fg(LOGO_COLOR),
+                        ),
+                        InputState::ShowingResult if app.diffusion_active() => Span::styled(
+                            "Space play/pause / ←→ step / Home End / Enter continue / Esc menu",
                             Style::default().fg(LOGO_COLOR),
                         ),
                         InputState::ShowingResult => Span::styled(
-                            "Enter continue / ↑↓ scroll / Esc menu",
+                            "Enter continue / (e) export / ↑↓ scroll / Esc menu",
                             Style::default().fg(LOGO_COLOR),
                         ),
                     }
@@ -415,6 +790,40 @@ mod tests {
             .unwrap();
     }
 
+    #[test]
+    fn test_ui_menu_shows_selected_algorithm() {
+        let backend = TestBackend::new(100, 40);
+        let mut terminal = Terminal::new(backend).unwrap();
+        let mut app = App::new();
+        app.select_next_algorithm();
+
+        terminal
+            .draw(|frame| {
+                ui(frame, &app);
+            })
+            .unwrap();
+
+        let buffer = terminal.backend().buffer();
+        let content: String = buffer.content().iter().map(|c| c.symbol()).collect();
+        assert!(content.contains(app.algorithm().name()));
+    }
+
+    #[test]
+    fn test_ui_sandbox_sac_mode_with_heatmap() {
+        let backend = TestBackend::new(80, 24);
+        let mut terminal = Terminal::new(backend).unwrap();
+        let mut app = App::new();
+        app.switch_to_sac();
+        app.original_text = "a".to_string();
+        app.process_sac();
+
+        terminal
+            .draw(|frame| {
+                ui(frame, &app);
+            })
+            .unwrap();
+    }
+
     #[test]
     fn test_ui_exit_modal() {
         let backend = TestBackend::new(80, 24);
@@ -518,4 +927,165 @@ mod tests {
             })
             .unwrap();
     }
+
+    #[test]
+    fn test_ui_scrolls_to_the_sensitivity_table() {
+        let mut app = App::new();
+        app.switch_to_automatic();
+        app.original_text = "abc".to_string();
+        app.input_state = Some(InputState::ShowingResult);
+        app.process_automatic();
+        app.histogram = None;
+        let header = avalanche_effect::app::sensitivity::table_header();
+        let row = app.messages.iter().position(|m| *m == header).unwrap();
+
+        let render = |app: &App| {
+            let backend = TestBackend::new(100, 24);
+            let mut terminal = Terminal::new(backend).unwrap();
+            terminal.draw(|frame| ui(frame, app)).unwrap();
+            let content: String = terminal
+                .backend()
+                .buffer()
+                .content()
+                .iter()
+                .map(|c| c.symbol())
+                .collect();
+            content
+        };
+
+        assert!(!render(&app).contains(header.trim()));
+
+        app.output_scroll_offset = row;
+        let content = render(&app);
+        assert!(content.contains(header.trim()));
+        assert!(!content.contains(&app.messages[1]));
+    }
+
+    #[test]
+    fn test_ui_diffusion_widget() {
+        let backend = TestBackend::new(100, 40);
+        let mut terminal = Terminal::new(backend).unwrap();
+        let mut app = App::new();
+        app.switch_to_diffusion();
+        app.original_text = "abc".to_string();
+        app.bit_index = Some(0);
+        app.input_state = Some(InputState::ShowingResult);
+        app.process_diffusion();
+        app.step_diffusion(10);
+
+        terminal
+            .draw(|frame| {
+                ui(frame, &app);
+            })
+            .unwrap();
+
+        let buffer = terminal.backend().buffer();
+        let content: String = buffer.content().iter().map(|c| c.symbol()).collect();
+        assert!(content.contains("SHA-256 round by round"));
+        assert!(content.contains("Round 10/64"));
+        assert!(content.contains("Flipped input bits: 0"));
+
+        let red_cells = buffer
+            .content()
+            .iter()
+            .filter(|c| c.fg == Color::Red)
+            .count();
+        assert_eq!(
+            red_cells,
+            2 * app.diffusion.as_ref().unwrap().differing_bits(10) as usize
+        );
+    }
+
+    #[test]
+    fn test_ui_automatic_histogram_fits_the_terminal() {
+        let mut app = App::new();
+        app.switch_to_automatic();
+        app.original_text = "abc".to_string();
+        app.input_state = Some(InputState::ShowingResult);
+        app.process_automatic();
+        assert_eq!(app.histogram.as_ref().unwrap().flips, 24);
+
+        let backend = TestBackend::new(100, 40);
+        let mut terminal = Terminal::new(backend).unwrap();
+        terminal
+            .draw(|frame| {
+                ui(frame, &app);
+            })
+            .unwrap();
+        let buffer = terminal.backend().buffer();
+        let content: String = buffer.content().iter().map(|c| c.symbol()).collect();
+        assert!(content.contains("Hamming distances over 24 flips"));
+        assert!(content.contains("binomial"));
+        assert!(content.contains("Analysis Results"));
+        assert!(content.contains("Hash function: SHA-256"));
+        assert!(buffer.content().iter().any(|c| c.fg == Color::Yellow));
+
+        // Too short for both: the report keeps the whole area.
+        let backend = TestBackend::new(80, 24);
+        let mut terminal = Terminal::new(backend).unwrap();
+        terminal
+            .draw(|frame| {
+                ui(frame, &app);
+            })
+            .unwrap();
+        let content: String = terminal
+            .backend()
+            .buffer()
+            .content()
+            .iter()
+            .map(|c| c.symbol())
+            .collect();
+        assert!(!content.contains("Hamming distances over"));
+    }
+
+    #[test]
+    fn test_ui_comparison_table() {
+        let backend = TestBackend::new(120, 40);
+        let mut terminal = Terminal::new(backend).unwrap();
+        let mut app = App::new();
+        app.switch_to_compare();
+        app.original_text = "abc".to_string();
+        app.input_state = Some(InputState::ShowingResult);
+        app.process_compare();
+        assert!(app.comparison_active());
+
+        terminal
+            .draw(|frame| {
+                ui(frame, &app);
+            })
+            .unwrap();
+
+        let buffer = terminal.backend().buffer();
+        let content: String = buffer.content().iter().map(|c| c.symbol()).collect();
+        for name in app.compared_names() {
+            assert!(content.contains(name), "missing column {}", name);
+        }
+        assert!(content.contains("Throughput"));
+        assert!(content.contains("SHA-256 vs BLAKE3"));
+    }
+
+    #[test]
+    fn test_ui_shows_gauge_while_job_runs() {
+        let backend = TestBackend::new(100, 24);
+        let mut terminal = Terminal::new(backend).unwrap();
+        let mut app = App::new();
+        app.run_in_background = true;
+        app.switch_to_automatic();
+        app.input_buffer = "a".repeat(1 << 20);
+        app.submit_input();
+        assert!(app.job_running());
+
+        terminal
+            .draw(|frame| {
+                ui(frame, &app);
+            })
+            .unwrap();
+        app.cancel_job();
+
+        let buffer = terminal.backend().buffer();
+        let content: String = buffer.content().iter().map(|c| c.symbol()).collect();
+        assert!(content.contains("Progress"));
+        assert!(content.contains("Esc / Ctrl+C cancel"));
+        assert!(!content.contains("Input"));
+    }
 }
@@ -32,13 +32,15 @@ fn test_manual_mode_complete_workflow() {
 fn test_automatic_mode_complete_workflow() {
     let mut app = App::new();
 
-    app.input_buffer = "test".to_string();
-    app.original_text = "test".to_string();
     app.switch_to_automatic();
 
     assert!(matches!(app.current_screen, CurrentScreen::Sandbox));
     assert!(matches!(app.current_mode, Some(SandboxMode::Automatic)));
 
+    app.input_buffer = "test".to_string();
+    app.submit_input();
+    assert!(matches!(app.input_state, Some(InputState::ShowingResult)));
+
     assert!(app.messages.iter().any(|m| m.contains("=== Summary ===")));
     assert!(app.messages.iter().any(|m| m.contains("Minimum:")));
     assert!(app.messages.iter().any(|m| m.contains("Maximum:")));
@@ -276,3 +278,63 @@ fn test_automatic_mode_parallel_processing() {
     assert!(max.contains("%"));
     assert!(avg.contains("%"));
 }
+
+// Everything random in one run, as bytes: SAC and BIC matrices, the weight
+// sweep, the bias counts, a Monte Carlo export and the automatic view.
+fn seeded_run(seed: u64, kind: random::MessageKind) -> Vec<u8> {
+    let sha256 = algorithms::ALGORITHMS[0];
+    let mut bytes = Vec::new();
+
+    let sac = statistics::SacMatrix::compute(sha256, 4, 64, seed, kind);
+    let bic = statistics::BicMatrix::compute(sha256, 2, 64, seed, kind);
+    for value in sac.probabilities.iter().chain(&bic.correlations) {
+        bytes.extend(value.to_le_bytes());
+    }
+    for point in automatic::weight_sweep(sha256, b"seeded", 8, 32, seed) {
+        bytes.extend(point.summary.mean.to_le_bytes());
+    }
+    for change in bias::output_bit_counts(sha256, b"seeded", 4, seed).changes {
+        bytes.extend(change.to_le_bytes());
+    }
+
+    let message = kind.message(4096, seed, 0);
+    let plan = sampling::SamplingPlan {
+        seed,
+        ..sampling::SamplingPlan::new(sampling::Strata::Bytes(1), sampling::Target::Width(4.0))
+    };
+    let report = automatic::analyze_monte_carlo(sha256, &message, &plan);
+    export::RunExport::from_automatic(&report)
+        .write(export::ExportFormat::Json, &mut bytes)
+        .unwrap();
+
+    let mut app = App::new();
+    app.seed = seed;
+    app.message_kind = kind;
+    app.original_text = "seeded".to_string();
+    app.process_automatic();
+    app.process_sac();
+    for line in app.messages {
+        if !line.starts_with("Computation time") {
+            bytes.extend(line.into_bytes());
+        }
+    }
+    bytes
+}
+
+#[test]
+fn test_same_seed_gives_identical_results_across_thread_counts() {
+    for kind in random::MessageKind::ALL {
+        let runs: Vec<Vec<u8>> = [1, 4]
+            .iter()
+            .map(|&threads| {
+                rayon::ThreadPoolBuilder::new()
+                    .num_threads(threads)
+                    .build()
+                    .unwrap()
+                    .install(|| seeded_run(0x5eed, kind))
+            })
+            .collect();
+        assert!(runs.windows(2).all(|w| w[0] == w[1]), "{:?}", kind);
+        assert_ne!(runs[0], seeded_run(0x5eee, kind), "{:?}", kind);
+    }
+}